@@ -32,7 +32,8 @@ export function newProfile() {
     steps: [],
     tags: [],
     hotkey: '',
-    schedule: null
+    schedule: null,
+    kill_on_switch: []
   };
 }
 
@@ -51,10 +52,18 @@ export function newStep(type = 'app') {
       return { ...base, target: '', check_running: true };
     case 'terminal':
       return { ...base, command: '', working_dir: '', keep_open: true };
+    case 'shell':
+      return { ...base, command: '', working_dir: '' };
     case 'folder':
       return { ...base, target: '' };
     case 'url':
       return { ...base, target: '' };
+    case 'http':
+      return { ...base, target: '', method: 'POST' };
+    case 'audio':
+      return { ...base, target: '' };
+    case 'wait_until':
+      return { ...base, time: '09:00' };
     default:
       return { ...base, target: '' };
   }
@@ -53,6 +53,10 @@ export function showProfileEditor(profile, isNew) {
         <label>Tags (comma separated)</label>
         <input type="text" id="pe-tags" value="${escapeAttr(tags)}" placeholder="Work, Dev, Gaming">
       </div>
+      <div class="form-group">
+        <label>Kill on switch (process names, comma separated)</label>
+        <input type="text" id="pe-kill-switch" value="${escapeAttr((profile.kill_on_switch || []).join(', '))}" placeholder="slack.exe, code.exe">
+      </div>
       <div class="form-group">
         <label>Hotkey (e.g. Ctrl+Shift+1)</label>
         <input type="text" id="pe-hotkey" value="${escapeAttr(profile.hotkey || '')}" placeholder="Click and press keys..." readonly>
@@ -64,6 +68,13 @@ export function showProfileEditor(profile, isNew) {
           <label for="pe-sched-enabled">Auto-launch on schedule</label>
         </div>
         <div class="form-row">
+          <div class="form-group">
+            <label>Trigger</label>
+            <select id="pe-sched-trigger">
+              <option value="time" ${schedule.trigger !== 'on_unlock' ? 'selected' : ''}>At time</option>
+              <option value="on_unlock" ${schedule.trigger === 'on_unlock' ? 'selected' : ''}>On unlock</option>
+            </select>
+          </div>
           <div class="form-group">
             <label>Time</label>
             <input type="time" id="pe-sched-time" value="${escapeAttr(schedule.time || '09:00')}">
@@ -73,6 +84,23 @@ export function showProfileEditor(profile, isNew) {
             <div class="day-checks">${dayChecks}</div>
           </div>
         </div>
+        <div class="form-row">
+          <div class="form-group">
+            <label>Condition</label>
+            <select id="pe-sched-condition">
+              <option value="">None</option>
+              <option value="wifi_ssid" ${schedule.condition?.wifi_ssid !== undefined ? 'selected' : ''}>On Wi-Fi network</option>
+              <option value="ac_only" ${schedule.condition?.power_source === 'ac_only' ? 'selected' : ''}>Plugged in (not on battery)</option>
+            </select>
+          </div>
+          <div class="form-group">
+            <label>Wi-Fi network (SSID)</label>
+            <div class="browse-row">
+              <input type="text" id="pe-sched-ssid" value="${escapeAttr(schedule.condition?.wifi_ssid || '')}" placeholder="Office Wi-Fi">
+              <button class="browse-btn" id="pe-current-ssid">Current</button>
+            </div>
+          </div>
+        </div>
       </div>
       <div class="modal-actions">
         <button class="btn-secondary" id="pe-cancel">Cancel</button>
@@ -97,6 +125,16 @@ export function showProfileEditor(profile, isNew) {
     });
     hotkeyInput.addEventListener('click', () => hotkeyInput.value = '');
 
+    document.getElementById('pe-current-ssid').addEventListener('click', async () => {
+      try {
+        const ssid = await invoke('get_current_ssid');
+        if (ssid) {
+          document.getElementById('pe-sched-ssid').value = ssid;
+          document.getElementById('pe-sched-condition').value = 'wifi_ssid';
+        }
+      } catch (e) { console.error(e); }
+    });
+
     document.getElementById('pe-name').focus();
     document.getElementById('pe-cancel').addEventListener('click', () => { hideModal(); resolve(null); });
     document.getElementById('pe-save').addEventListener('click', () => {
@@ -104,13 +142,21 @@ export function showProfileEditor(profile, isNew) {
       profile.description = document.getElementById('pe-desc').value.trim();
       profile.tags = document.getElementById('pe-tags').value.split(',').map(t => t.trim()).filter(Boolean);
       profile.hotkey = document.getElementById('pe-hotkey').value.trim();
+      profile.kill_on_switch = document.getElementById('pe-kill-switch').value.split(',').map(t => t.trim()).filter(Boolean);
 
       const schedEnabled = document.getElementById('pe-sched-enabled').checked;
       const schedTime = document.getElementById('pe-sched-time').value || '09:00';
       const schedDays = [...document.querySelectorAll('.pe-day:checked')].map(cb => parseInt(cb.value));
-
-      if (schedEnabled || schedTime !== '09:00' || schedDays.length > 0) {
-        profile.schedule = { enabled: schedEnabled, time: schedTime, days: schedDays };
+      const schedTrigger = document.getElementById('pe-sched-trigger').value;
+      const schedSsid = document.getElementById('pe-sched-ssid').value.trim();
+      const condType = document.getElementById('pe-sched-condition').value;
+      let schedCondition = null;
+      if (condType === 'wifi_ssid' && schedSsid) schedCondition = { wifi_ssid: schedSsid };
+      if (condType === 'ac_only') schedCondition = { power_source: 'ac_only' };
+
+      if (schedEnabled || schedTime !== '09:00' || schedDays.length > 0 || schedTrigger !== 'time' || schedCondition) {
+        profile.schedule = { enabled: schedEnabled, time: schedTime, days: schedDays, trigger: schedTrigger };
+        if (schedCondition) profile.schedule.condition = schedCondition;
       } else {
         profile.schedule = null;
       }
@@ -127,8 +173,9 @@ export function showProfileEditor(profile, isNew) {
 // ── Step editor ──
 export function showStepEditor(step, isNew) {
   return new Promise((resolve) => {
-    const typeOptions = ['app', 'terminal', 'folder', 'url']
-      .map(t => `<option value="${t}" ${step.type === t ? 'selected' : ''}>${t === 'terminal' ? 'Terminal (CMD)' : t.charAt(0).toUpperCase() + t.slice(1)}</option>`)
+    const typeLabels = { terminal: 'Terminal (CMD)', shell: 'Shell (hidden)', http: 'HTTP request', audio: 'Audio device', wait_until: 'Wait until time' };
+    const typeOptions = ['app', 'terminal', 'shell', 'folder', 'url', 'http', 'audio', 'wait_until']
+      .map(t => `<option value="${t}" ${step.type === t ? 'selected' : ''}>${typeLabels[t] || t.charAt(0).toUpperCase() + t.slice(1)}</option>`)
       .join('');
 
     showModal(`
@@ -138,6 +185,10 @@ export function showStepEditor(step, isNew) {
           <label>Name</label>
           <input type="text" id="se-name" value="${escapeAttr(step.name)}" placeholder="Step name">
         </div>
+        <div class="form-group" style="max-width:70px">
+          <label>Icon</label>
+          <input type="text" id="se-icon" value="${escapeAttr(step.icon || '')}" placeholder="&#128640;" maxlength="8">
+        </div>
         <div class="form-group" style="max-width:160px">
           <label>Type</label>
           <select id="se-type">${typeOptions}</select>
@@ -145,6 +196,10 @@ export function showStepEditor(step, isNew) {
       </div>
       <div id="se-fields"></div>
       <div class="form-row">
+        <div class="form-group">
+          <label>Delay before (ms)</label>
+          <input type="number" id="se-delay-before" value="${step.delay_before || 0}" min="0" step="100">
+        </div>
         <div class="form-group">
           <label>Delay after (ms)</label>
           <input type="number" id="se-delay" value="${step.delay_after || 500}" min="0" step="100">
@@ -154,6 +209,14 @@ export function showStepEditor(step, isNew) {
           <input type="text" id="se-process" value="${escapeAttr(step.process_name || '')}" placeholder="e.g. chrome.exe">
         </div>
       </div>
+      <div class="form-group">
+        <label>If this step fails</label>
+        <select id="se-on-failure">
+          <option value="continue" ${!step.on_failure || step.on_failure === 'continue' ? 'selected' : ''}>Continue with the next step</option>
+          <option value="abort" ${step.on_failure === 'abort' ? 'selected' : ''}>Stop the launch</option>
+          <option value="prompt" ${step.on_failure === 'prompt' ? 'selected' : ''}>Ask me</option>
+        </select>
+      </div>
       <div class="modal-actions">
         <button class="btn-secondary" id="se-cancel">Cancel</button>
         <button class="btn-primary" id="se-save">Save</button>
@@ -172,9 +235,15 @@ export function showStepEditor(step, isNew) {
     document.getElementById('se-cancel').addEventListener('click', () => { hideModal(); resolve(null); });
     document.getElementById('se-save').addEventListener('click', () => {
       step.name = document.getElementById('se-name').value.trim() || 'Unnamed';
+      const icon = document.getElementById('se-icon').value.trim();
+      if (icon) step.icon = icon; else delete step.icon;
       step.type = document.getElementById('se-type').value;
       step.delay_after = parseInt(document.getElementById('se-delay').value) || 500;
+      const delayBefore = parseInt(document.getElementById('se-delay-before').value) || 0;
+      if (delayBefore > 0) step.delay_before = delayBefore; else delete step.delay_before;
       step.process_name = document.getElementById('se-process').value.trim();
+      const onFailure = document.getElementById('se-on-failure').value;
+      if (onFailure !== 'continue') step.on_failure = onFailure; else delete step.on_failure;
       readStepFields(step);
       hideModal();
       resolve(step);
@@ -201,6 +270,27 @@ function renderStepFields(step) {
           <input type="checkbox" id="se-check-running" ${step.check_running !== false ? 'checked' : ''}>
           <label for="se-check-running">Skip if already running</label>
         </div>
+        <div class="form-check">
+          <input type="checkbox" id="se-client-first" ${step.launch_client_first ? 'checked' : ''}>
+          <label for="se-client-first">Start the store client first (Steam, Epic, GOG, Ubisoft URIs; counts toward the step timeout)</label>
+        </div>
+        <div class="form-group">
+          <label>Window title contains (for apps sharing a process name)</label>
+          <input type="text" id="se-window-title" value="${escapeAttr(step.window_title_match || '')}" placeholder="e.g. Slack">
+        </div>
+        <div class="form-group">
+          <label>Wait for a window titled (before the next step)</label>
+          <input type="text" id="se-wait-window" value="${escapeAttr(step.wait_for_window || '')}" placeholder="e.g. Visual Studio Code">
+        </div>
+        <div class="form-check">
+          <input type="checkbox" id="se-restart-on-crash" ${step.restart_on_crash ? 'checked' : ''}>
+          <label for="se-restart-on-crash">Restart if it crashes</label>
+        </div>
+        <div class="form-check">
+          <input type="checkbox" id="se-match-children" ${step.match_children ? 'checked' : ''}>
+          <label for="se-match-children">Also close helper processes (name*)</label>
+        </div>
+        ${runAsFields(step)}
       `;
       document.getElementById('se-browse-file').addEventListener('click', async () => {
         try {
@@ -222,7 +312,11 @@ function renderStepFields(step) {
       container.innerHTML = `
         <div class="form-group">
           <label>Command</label>
-          <input type="text" id="se-command" value="${escapeAttr(step.command || '')}" placeholder="npm run dev">
+          <div class="browse-row">
+            <input type="text" id="se-command" value="${escapeAttr(step.command || '')}" placeholder="npm run dev">
+            <button class="browse-btn" id="se-preview-command" title="Show the exact command line">Preview</button>
+          </div>
+          <code id="se-command-preview" class="command-preview" style="display:none"></code>
         </div>
         <div class="form-group">
           <label>Working Directory</label>
@@ -235,6 +329,46 @@ function renderStepFields(step) {
           <input type="checkbox" id="se-keep-open" ${step.keep_open !== false ? 'checked' : ''}>
           <label for="se-keep-open">Keep terminal open</label>
         </div>
+        ${runAsFields(step)}
+      `;
+      document.getElementById('se-browse-dir').addEventListener('click', async () => {
+        try {
+          const path = await invoke('browse_folder');
+          if (path) document.getElementById('se-workdir').value = path;
+        } catch (e) { console.error(e); }
+      });
+      document.getElementById('se-preview-command').addEventListener('click', async () => {
+        const preview = document.getElementById('se-command-preview');
+        const draft = {
+          ...step,
+          command: document.getElementById('se-command').value.trim(),
+          working_dir: document.getElementById('se-workdir').value.trim(),
+          keep_open: document.getElementById('se-keep-open').checked
+        };
+        delete draft.run_as_user;
+        readRunAs(draft);
+        try {
+          preview.textContent = await invoke('preview_terminal_command', { step: draft });
+        } catch (e) {
+          preview.textContent = e?.message ?? e;
+        }
+        preview.style.display = '';
+      });
+      break;
+
+    case 'shell':
+      container.innerHTML = `
+        <div class="form-group">
+          <label>Command (runs hidden; a non-zero exit fails the step)</label>
+          <input type="text" id="se-command" value="${escapeAttr(step.command || '')}" placeholder="git -C C:\\project pull &amp;&amp; npm install">
+        </div>
+        <div class="form-group">
+          <label>Working Directory</label>
+          <div class="browse-row">
+            <input type="text" id="se-workdir" value="${escapeAttr(step.working_dir || '')}" placeholder="C:\\project">
+            <button class="browse-btn" id="se-browse-dir">Browse</button>
+          </div>
+        </div>
       `;
       document.getElementById('se-browse-dir').addEventListener('click', async () => {
         try {
@@ -265,11 +399,103 @@ function renderStepFields(step) {
     case 'url':
       container.innerHTML = `
         <div class="form-group">
-          <label>URL</label>
-          <input type="text" id="se-target" value="${escapeAttr(step.target || '')}" placeholder="https://example.com">
+          <label>URL (one per line to open several)</label>
+          <textarea id="se-target" rows="3" placeholder="https://example.com">${escapeAttr(step.target || '')}</textarea>
+        </div>
+      `;
+      break;
+
+    case 'audio':
+      container.innerHTML = `
+        <div class="form-group">
+          <label>Default audio device</label>
+          <div class="browse-row">
+            <input type="text" id="se-target" value="${escapeAttr(step.target || '')}" placeholder="Headphones (USB Audio)" list="se-audio-devices">
+            <datalist id="se-audio-devices"></datalist>
+          </div>
         </div>
       `;
+      invoke('list_audio_devices').then((devices) => {
+        const list = document.getElementById('se-audio-devices');
+        if (!list) return;
+        list.innerHTML = devices
+          .map(d => `<option value="${escapeAttr(d.name)}">${d.kind === 'recording' ? 'Recording' : 'Playback'}${d.is_default ? ' (default)' : ''}</option>`)
+          .join('');
+      }).catch((e) => console.error(e));
       break;
+
+    case 'wait_until':
+      container.innerHTML = `
+        <div class="form-group">
+          <label>Wait until (skipped if the time has passed)</label>
+          <input type="time" id="se-time" value="${escapeAttr(step.time || '09:00')}">
+        </div>
+      `;
+      break;
+
+    case 'http': {
+      const methods = ['POST', 'GET', 'PUT', 'PATCH', 'DELETE']
+        .map(m => `<option value="${m}" ${(step.method || 'POST') === m ? 'selected' : ''}>${m}</option>`)
+        .join('');
+      const headers = Object.entries(step.headers || {}).map(([k, v]) => `${k}: ${v}`).join('\n');
+      container.innerHTML = `
+        <div class="form-row">
+          <div class="form-group" style="max-width:120px">
+            <label>Method</label>
+            <select id="se-method">${methods}</select>
+          </div>
+          <div class="form-group">
+            <label>URL</label>
+            <input type="text" id="se-target" value="${escapeAttr(step.target || '')}" placeholder="https://hooks.example.com/...">
+          </div>
+        </div>
+        <div class="form-group">
+          <label>Headers (one "Name: value" per line)</label>
+          <textarea id="se-headers" rows="3" placeholder="Authorization: Bearer %API_TOKEN%">${escapeAttr(headers)}</textarea>
+        </div>
+        <div class="form-group">
+          <label>Body</label>
+          <textarea id="se-body" rows="4" placeholder='{"status": "working"}'>${escapeAttr(step.body || '')}</textarea>
+        </div>
+      `;
+      break;
+    }
+  }
+
+  // Tidy pasted paths ("Copy as path" quotes, file:// URLs, doubled slashes)
+  if (type === 'app' || type === 'folder') {
+    const target = document.getElementById('se-target');
+    target.addEventListener('blur', async () => {
+      if (!target.value.trim()) return;
+      try {
+        target.value = await invoke('normalize_target', { input: target.value, stepType: type });
+      } catch (e) { console.error(e); }
+    });
+  }
+}
+
+// Run-as-user fields shared by app and terminal steps
+function runAsFields(step) {
+  const runAs = step.run_as_user || {};
+  return `
+    <div class="form-group">
+      <label>Run as user (Windows asks for the password)</label>
+      <input type="text" id="se-run-as" value="${escapeAttr(runAs.username || '')}" placeholder="e.g. DOMAIN\\svc-build">
+    </div>
+    <div class="form-check">
+      <input type="checkbox" id="se-run-as-savecred" ${runAs.save_credentials ? 'checked' : ''}>
+      <label for="se-run-as-savecred">Let Windows remember the password (saved in Credential Manager)</label>
+    </div>
+  `;
+}
+
+function readRunAs(step) {
+  const username = document.getElementById('se-run-as')?.value.trim();
+  if (username) {
+    step.run_as_user = {
+      username,
+      save_credentials: document.getElementById('se-run-as-savecred').checked
+    };
   }
 }
 
@@ -279,9 +505,19 @@ function readStepFields(step) {
   // Clean up fields from other types
   delete step.target;
   delete step.check_running;
+  delete step.launch_client_first;
+  delete step.run_as_user;
+  delete step.window_title_match;
+  delete step.wait_for_window;
+  delete step.restart_on_crash;
+  delete step.match_children;
   delete step.command;
   delete step.working_dir;
   delete step.keep_open;
+  delete step.method;
+  delete step.headers;
+  delete step.body;
+  delete step.time;
 
   switch (type) {
     case 'app': {
@@ -289,6 +525,17 @@ function readStepFields(step) {
       const checkRunning = document.getElementById('se-check-running');
       step.target = target ? target.value.trim() : '';
       step.check_running = checkRunning ? checkRunning.checked : true;
+      const clientFirst = document.getElementById('se-client-first');
+      if (clientFirst && clientFirst.checked) step.launch_client_first = true;
+      const windowTitle = document.getElementById('se-window-title');
+      if (windowTitle && windowTitle.value.trim()) step.window_title_match = windowTitle.value.trim();
+      const waitWindow = document.getElementById('se-wait-window');
+      if (waitWindow && waitWindow.value.trim()) step.wait_for_window = waitWindow.value.trim();
+      const restart = document.getElementById('se-restart-on-crash');
+      if (restart && restart.checked) step.restart_on_crash = true;
+      const matchChildren = document.getElementById('se-match-children');
+      if (matchChildren && matchChildren.checked) step.match_children = true;
+      readRunAs(step);
       break;
     }
     case 'terminal': {
@@ -298,14 +545,36 @@ function readStepFields(step) {
       step.command = command ? command.value.trim() : '';
       step.working_dir = workdir ? workdir.value.trim() : '';
       step.keep_open = keepOpen ? keepOpen.checked : true;
+      readRunAs(step);
       break;
     }
+    case 'shell':
+      step.command = document.getElementById('se-command').value.trim();
+      step.working_dir = document.getElementById('se-workdir').value.trim();
+      break;
     case 'folder':
-    case 'url': {
+    case 'url':
+    case 'audio': {
       const target = document.getElementById('se-target');
       step.target = target ? target.value.trim() : '';
       break;
     }
+    case 'http': {
+      step.target = document.getElementById('se-target').value.trim();
+      step.method = document.getElementById('se-method').value;
+      const headers = {};
+      for (const line of document.getElementById('se-headers').value.split('\n')) {
+        const idx = line.indexOf(':');
+        if (idx > 0) headers[line.slice(0, idx).trim()] = line.slice(idx + 1).trim();
+      }
+      if (Object.keys(headers).length > 0) step.headers = headers;
+      const body = document.getElementById('se-body').value;
+      if (body.trim()) step.body = body;
+      break;
+    }
+    case 'wait_until':
+      step.time = document.getElementById('se-time').value;
+      break;
   }
 }
 
@@ -316,9 +585,24 @@ export function showSettings(settings) {
       <div class="modal-title">Settings</div>
       <div class="settings-section">
         <div class="form-group">
-          <label>Default launch delay (ms)</label>
+          <label>Minimum delay between steps (ms)</label>
           <input type="number" id="set-delay" value="${settings.launch_delay_ms || 500}" min="0" step="100">
         </div>
+        <div class="form-group">
+          <label>Step timeout (seconds, 0 = never)</label>
+          <input type="number" id="set-step-timeout" value="${settings.step_timeout_secs ?? 15}" min="0" step="1">
+        </div>
+        <div class="form-group">
+          <label>Trusted URI schemes (comma-separated, launch without asking)</label>
+          <input type="text" id="set-trusted-schemes" value="${escapeAttr((settings.trusted_uri_schemes || []).join(', '))}" placeholder="e.g. todoist, linear">
+        </div>
+        <div class="form-group">
+          <label>Tray menu order</label>
+          <select id="set-tray-layout">
+            <option value="show_first" ${settings.tray_layout !== 'profiles_first' ? 'selected' : ''}>Show WorkSwitch first</option>
+            <option value="profiles_first" ${settings.tray_layout === 'profiles_first' ? 'selected' : ''}>Profiles first</option>
+          </select>
+        </div>
         <div class="form-check">
           <input type="checkbox" id="set-minimized" ${settings.start_minimized ? 'checked' : ''}>
           <label for="set-minimized">Start minimized</label>
@@ -327,21 +611,62 @@ export function showSettings(settings) {
           <input type="checkbox" id="set-tray" ${settings.minimize_to_tray !== false ? 'checked' : ''}>
           <label for="set-tray">Minimize to tray on close</label>
         </div>
+        <div class="form-check">
+          <input type="checkbox" id="set-delay-last" ${settings.delay_after_last_step ? 'checked' : ''}>
+          <label for="set-delay-last">Wait after the last step too</label>
+        </div>
+        <div class="form-check">
+          <input type="checkbox" id="set-minimize-after" ${settings.minimize_after_launch ? 'checked' : ''}>
+          <label for="set-minimize-after">Minimize after launching a profile</label>
+        </div>
+        <div class="form-check">
+          <input type="checkbox" id="set-new-desktop" ${settings.launch_on_new_virtual_desktop ? 'checked' : ''}>
+          <label for="set-new-desktop">Launch on a new virtual desktop</label>
+        </div>
+        <div class="form-check">
+          <input type="checkbox" id="set-log-terminal" ${settings.log_terminal_output ? 'checked' : ''}>
+          <label for="set-log-terminal">Log output of terminals that close when done</label>
+        </div>
         <div class="form-check">
           <input type="checkbox" id="set-close-switch" ${settings.close_on_switch !== false ? 'checked' : ''}>
-          <label for="set-close-switch">Offer to close apps when switching profiles</label>
+          <label for="set-close-switch">Close the previous profile's apps when switching profiles</label>
+        </div>
+        <div class="form-check">
+          <input type="checkbox" id="set-close-exit" ${settings.close_on_exit ? 'checked' : ''}>
+          <label for="set-close-exit">Close launched apps when quitting</label>
+        </div>
+        <div class="form-check">
+          <input type="checkbox" id="set-confirm-close-exit" ${settings.confirm_close_on_exit !== false ? 'checked' : ''}>
+          <label for="set-confirm-close-exit">Ask before closing launched apps on quit</label>
         </div>
         <div class="form-check">
           <input type="checkbox" id="set-autostart" ${settings.auto_start_with_windows ? 'checked' : ''}>
           <label for="set-autostart">Launch with Windows</label>
         </div>
       </div>
+      <div class="settings-about" id="set-about"></div>
       <div class="modal-actions">
+        <button class="btn-secondary" id="set-reset" style="margin-right:auto">Reset to defaults</button>
         <button class="btn-secondary" id="set-cancel">Cancel</button>
         <button class="btn-primary" id="set-save">Save</button>
       </div>
     `);
 
+    // Reflect the registry rather than the saved flag, which can go stale
+    invoke('get_auto_start').then((enabled) => {
+      const box = document.getElementById('set-autostart');
+      if (box) box.checked = enabled;
+    }).catch((e) => console.error(e));
+
+    // Version line for bug reports; the config path is in the tooltip
+    invoke('get_system_info').then((info) => {
+      const about = document.getElementById('set-about');
+      if (!about) return;
+      about.textContent = `WorkSwitch ${info.app_version} on ${info.os_version || info.os} (${info.arch})`;
+      about.title = info.config_path;
+    }).catch((e) => console.error(e));
+
+    document.getElementById('set-reset').addEventListener('click', () => { hideModal(); resolve('reset'); });
     document.getElementById('set-cancel').addEventListener('click', () => { hideModal(); resolve(null); });
     document.getElementById('set-save').addEventListener('click', async () => {
       const autoStart = document.getElementById('set-autostart').checked;
@@ -351,12 +676,22 @@ export function showSettings(settings) {
       } catch (e) {
         console.error('Failed to set auto-start:', e);
       }
+      const stepTimeout = parseInt(document.getElementById('set-step-timeout').value);
       const result = {
         ...settings,
         launch_delay_ms: parseInt(document.getElementById('set-delay').value) || 500,
+        step_timeout_secs: Number.isNaN(stepTimeout) ? 15 : Math.max(0, stepTimeout),
         start_minimized: document.getElementById('set-minimized').checked,
         minimize_to_tray: document.getElementById('set-tray').checked,
+        tray_layout: document.getElementById('set-tray-layout').value,
+        minimize_after_launch: document.getElementById('set-minimize-after').checked,
+        launch_on_new_virtual_desktop: document.getElementById('set-new-desktop').checked,
+        log_terminal_output: document.getElementById('set-log-terminal').checked,
+        delay_after_last_step: document.getElementById('set-delay-last').checked,
+        trusted_uri_schemes: document.getElementById('set-trusted-schemes').value.split(',').map(s => s.trim().replace(/:+$/, '').toLowerCase()).filter(Boolean),
         close_on_switch: document.getElementById('set-close-switch').checked,
+        close_on_exit: document.getElementById('set-close-exit').checked,
+        confirm_close_on_exit: document.getElementById('set-confirm-close-exit').checked,
         auto_start_with_windows: autoStart
       };
       hideModal();
@@ -365,38 +700,6 @@ export function showSettings(settings) {
   });
 }
 
-// ── Close-on-switch dialog ──
-export function showCloseOnSwitch(processes) {
-  return new Promise((resolve) => {
-    const items = processes.map((p, i) => `
-      <li class="close-process-item">
-        <input type="checkbox" id="cos-${i}" checked>
-        <label for="cos-${i}">${escapeHtml(p)}</label>
-      </li>
-    `).join('');
-
-    showModal(`
-      <div class="modal-title">Close Running Apps?</div>
-      <p style="color: var(--text-secondary); margin-bottom: 4px;">The following apps from the previous profile are still running:</p>
-      <ul class="close-process-list">${items}</ul>
-      <div class="modal-actions">
-        <button class="btn-secondary" id="cos-skip">Skip</button>
-        <button class="btn-primary" id="cos-close">Close Selected</button>
-      </div>
-    `);
-
-    document.getElementById('cos-skip').addEventListener('click', () => { hideModal(); resolve([]); });
-    document.getElementById('cos-close').addEventListener('click', () => {
-      const toClose = [];
-      processes.forEach((p, i) => {
-        if (document.getElementById(`cos-${i}`).checked) toClose.push(p);
-      });
-      hideModal();
-      resolve(toClose);
-    });
-  });
-}
-
 // ── Launch history dialog ──
 export function showLaunchHistory(history) {
   return new Promise((resolve) => {
@@ -466,6 +769,76 @@ export function showDuplicateNamePrompt(defaultName) {
   });
 }
 
+// ── Import from URL prompt ──
+export function showImportUrlPrompt(url = '', error = '') {
+  return new Promise((resolve) => {
+    showModal(`
+      <div class="modal-title">Import Profile from URL</div>
+      <div class="form-group">
+        <label>HTTPS link to the profile JSON (for a gist, use the Raw link)</label>
+        <input type="text" id="import-url" value="${escapeAttr(url)}" placeholder="https://gist.githubusercontent.com/...">
+      </div>
+      ${error ? `<p style="color: var(--danger); margin-bottom: 8px;">${escapeHtml(error)}</p>` : ''}
+      <div class="modal-actions">
+        <button class="btn-secondary" id="import-url-cancel">Cancel</button>
+        <button class="btn-primary" id="import-url-ok">Import</button>
+      </div>
+    `);
+
+    const input = document.getElementById('import-url');
+    input.focus();
+    input.select();
+    document.getElementById('import-url-cancel').addEventListener('click', () => { hideModal(); resolve(null); });
+    document.getElementById('import-url-ok').addEventListener('click', () => {
+      const value = input.value.trim();
+      hideModal();
+      resolve(value || null);
+    });
+    input.addEventListener('keydown', (e) => {
+      if (e.key === 'Enter') document.getElementById('import-url-ok').click();
+    });
+  });
+}
+
+// ── Untrusted URI prompt ──
+// Resolves 'once', 'always' (trust the schemes from now on) or null
+export function showUntrustedUriPrompt(untrusted) {
+  return new Promise((resolve) => {
+    const rows = untrusted.map(u =>
+      `<li><strong>${escapeHtml(u.step_name)}</strong> opens a <code>${escapeHtml(u.scheme)}:</code> link</li>`
+    ).join('');
+    showModal(`
+      <div class="modal-title">Open unfamiliar links?</div>
+      <p style="color: var(--text-secondary); margin-bottom: 8px;">These steps open URI types WorkSwitch doesn't recognize as safe. Only continue if you trust where this profile came from.</p>
+      <ul style="margin: 0 0 8px 18px;">${rows}</ul>
+      <div class="modal-actions">
+        <button class="btn-secondary" id="uri-cancel">Cancel</button>
+        <button class="btn-secondary" id="uri-always">Always Allow</button>
+        <button class="btn-primary" id="uri-once">Launch Once</button>
+      </div>
+    `);
+    document.getElementById('uri-cancel').addEventListener('click', () => { hideModal(); resolve(null); });
+    document.getElementById('uri-always').addEventListener('click', () => { hideModal(); resolve('always'); });
+    document.getElementById('uri-once').addEventListener('click', () => { hideModal(); resolve('once'); });
+  });
+}
+
+// ── Failed step prompt ──
+export function showFailurePrompt(stepName, error) {
+  return new Promise((resolve) => {
+    showModal(`
+      <div class="modal-title">${escapeHtml(stepName)} failed</div>
+      <p style="color: var(--text-secondary); margin-bottom: 8px;">${escapeHtml(error || '')}</p>
+      <div class="modal-actions">
+        <button class="btn-danger" id="fail-stop">Stop Launch</button>
+        <button class="btn-primary" id="fail-continue">Continue</button>
+      </div>
+    `);
+    document.getElementById('fail-stop').addEventListener('click', () => { hideModal(); resolve(false); });
+    document.getElementById('fail-continue').addEventListener('click', () => { hideModal(); resolve(true); });
+  });
+}
+
 // ── App picker ──
 function showAppPicker() {
   return new Promise((resolve) => {
@@ -504,25 +877,24 @@ function showAppPicker() {
     document.getElementById('app-picker-search').focus();
 
     // Scan apps
-    invoke('scan_apps').then((apps) => {
+    invoke('scan_apps_grouped').then((groups) => {
       const body = document.getElementById('app-picker-body');
       if (!body) return; // picker was closed
 
+      const apps = Object.values(groups).flat();
       if (apps.length === 0) {
         body.innerHTML = '<div class="app-picker-empty">No applications found</div>';
         return;
       }
 
-      // Group by source
-      const groups = {};
-      const sourceOrder = ['steam', 'epic', 'windows'];
+      // Known sources first, then any others alphabetically
+      const knownOrder = ['steam', 'epic', 'windows'];
+      const sourceOrder = [
+        ...knownOrder.filter(src => groups[src]),
+        ...Object.keys(groups).filter(src => !knownOrder.includes(src)).sort()
+      ];
       const sourceLabels = { steam: 'Steam', epic: 'Epic Games', windows: 'Installed' };
 
-      for (const app of apps) {
-        if (!groups[app.source]) groups[app.source] = [];
-        groups[app.source].push(app);
-      }
-
       const renderList = (filter) => {
         const lowerFilter = (filter || '').toLowerCase();
         let html = '';
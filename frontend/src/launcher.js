@@ -1,3 +1,6 @@
+import { showFailurePrompt, showUntrustedUriPrompt } from './dialogs.js';
+import { getConfig, saveConfig } from './config.js';
+
 const { invoke } = window.__TAURI__.core;
 const { listen } = window.__TAURI__.event;
 
@@ -6,12 +9,17 @@ let _progressUnlisten = null;
 let _completeUnlisten = null;
 let _cancelledUnlisten = null;
 let _errorUnlisten = null;
+let _failedUnlisten = null;
+let _waitingUnlisten = null;
+let _restartedUnlisten = null;
+let _abortedUnlisten = null;
+let _promptUnlisten = null;
 
 export function isLaunching() {
   return _launching;
 }
 
-export async function startLaunch(steps, defaultDelay) {
+export async function startLaunch(steps, defaultDelay, profileId = null) {
   if (_launching) return;
   _launching = true;
 
@@ -22,14 +30,114 @@ export async function startLaunch(steps, defaultDelay) {
     return;
   }
 
+  const allowUnsafe = await confirmUntrustedUris(enabledSteps, profileId);
+  if (allowUnsafe === null) {
+    _launching = false;
+    return;
+  }
+
   updateLaunchUI(true);
   setStatus('Launching...');
   showProgress(true);
   setProgress(0);
+  await listenLaunchEvents();
+
+  // Don't await - the invoke resolves when launch is done, but events handle UI updates.
+  // We catch errors separately so the UI never gets stuck.
+  invoke('launch_profile', { steps: enabledSteps, defaultDelay, profileId, allowUnsafe }).catch((err) => {
+    cleanup();
+    // Backend errors arrive as { kind, message }
+    setStatus('Launch error: ' + (err?.message ?? err));
+    showProgress(false);
+    clearStepHighlights();
+  });
+}
+
+// Cancels the running launch and starts over with the (possibly edited) steps
+export async function restartLaunch(steps, defaultDelay, profileId = null) {
+  if (!_launching) return startLaunch(steps, defaultDelay, profileId);
+
+  const enabledSteps = steps.filter(s => s.enabled);
+  if (enabledSteps.length === 0) {
+    await cancelLaunch();
+    return;
+  }
+
+  const allowUnsafe = await confirmUntrustedUris(enabledSteps, profileId);
+  if (allowUnsafe === null) return;
+
+  // Drop the old run's listeners so its launch-cancelled doesn't reset the UI,
+  // and pick up events again once the backend says the new run has begun
+  unlistenAll();
+  setStatus('Restarting...');
+  clearStepHighlights();
+  setProgress(0);
+  _restartedUnlisten = await listen('launch-restarted', async () => {
+    if (_restartedUnlisten) { _restartedUnlisten(); _restartedUnlisten = null; }
+    await listenLaunchEvents();
+  });
+
+  invoke('restart_launch', { steps: enabledSteps, defaultDelay, profileId, allowUnsafe }).catch((err) => {
+    cleanup();
+    setStatus('Launch error: ' + (err?.message ?? err));
+    showProgress(false);
+    clearStepHighlights();
+  });
+}
+
+// Asks before opening URI schemes the backend doesn't know to be safe,
+// counting the profile's pre/post steps too.
+// Returns whether to pass allowUnsafe, or null if the user backed out.
+async function confirmUntrustedUris(steps, profileId) {
+  let untrusted = [];
+  try {
+    untrusted = await invoke('find_untrusted_uris', { steps, profileId });
+  } catch (e) {
+    // The launch itself still refuses them, so just let it report the error
+    console.error('URI check failed:', e);
+  }
+  if (untrusted.length === 0) return false;
+
+  const choice = await showUntrustedUriPrompt(untrusted);
+  if (!choice) return null;
+  if (choice === 'always') {
+    const config = getConfig();
+    const trusted = new Set(config.settings.trusted_uri_schemes || []);
+    for (const u of untrusted) trusted.add(u.scheme);
+    config.settings.trusted_uri_schemes = [...trusted];
+    await saveConfig();
+  }
+  return true;
+}
+
+// Shows progress and the cancel button for a launch the scheduler started
+export async function followScheduledLaunch(profileName) {
+  if (_launching) return;
+  _launching = true;
+
+  updateLaunchUI(true);
+  setStatus(`Scheduled launch: ${profileName}`);
+  showProgress(true);
+  setProgress(0);
+  await listenLaunchEvents();
 
+  _failedUnlisten = await listen('scheduled-launch-failed', (event) => {
+    cleanup();
+    setStatus(`Scheduled launch failed: ${event.payload.error}`);
+    showProgress(false);
+    clearStepHighlights();
+  });
+}
+
+async function listenLaunchEvents() {
   // Listen for progress events
   _progressUnlisten = await listen('launch-progress', (event) => {
-    const { step_name, current, total } = event.payload;
+    const { step_name, current, total, phase } = event.payload;
+    if (phase && phase !== 'main') {
+      // Pre/post hook steps have no cards in the step list
+      setStatus(`Running ${phase}-step: ${step_name} (${current}/${total})`);
+      return;
+    }
     setStatus(`Launching: ${step_name} (${current}/${total})`);
     setProgress((current / total) * 100);
 
@@ -64,18 +172,37 @@ export async function startLaunch(steps, defaultDelay) {
   });
 
   _errorUnlisten = await listen('launch-step-error', (event) => {
-    const { step_name, error } = event.payload;
-    setStatus(`Warning: ${step_name} failed (${error}) - continuing...`);
+    const { step_name, error, log_path } = event.payload;
+    const log = log_path ? ` - log: ${log_path}` : '';
+    setStatus(`Warning: ${step_name} failed (${error})${log} - continuing...`);
   });
 
-  // Don't await - the invoke resolves when launch is done, but events handle UI updates.
-  // We catch errors separately so the UI never gets stuck.
-  invoke('launch_profile', { steps: enabledSteps, defaultDelay }).catch((err) => {
+  _abortedUnlisten = await listen('launch-aborted', (event) => {
+    const { step_name, error } = event.payload;
     cleanup();
-    setStatus('Launch error: ' + err);
+    setStatus(`Launch stopped: ${step_name} failed (${error})`);
     showProgress(false);
     clearStepHighlights();
   });
+
+  _promptUnlisten = await listen('launch-failure-prompt', async (event) => {
+    const { step_name, error } = event.payload;
+    setStatus(`${step_name} failed - waiting for your answer...`);
+    const keepGoing = await showFailurePrompt(step_name, error);
+    try {
+      await invoke('resolve_failure_prompt', { continueLaunch: keepGoing });
+    } catch (e) {
+      // The launch was cancelled while the prompt was open
+      console.error('Failure prompt error:', e);
+    }
+  });
+
+  _waitingUnlisten = await listen('launch-waiting', (event) => {
+    const { step_name, window, until } = event.payload;
+    setStatus(until
+      ? `Waiting until ${until} (${step_name})...`
+      : `Waiting for "${window}" window (${step_name})...`);
+  });
 }
 
 export async function cancelLaunch() {
@@ -90,17 +217,28 @@ export async function cancelLaunch() {
 function cleanup() {
   _launching = false;
   updateLaunchUI(false);
+  unlistenAll();
+}
+
+function unlistenAll() {
   if (_progressUnlisten) { _progressUnlisten(); _progressUnlisten = null; }
   if (_completeUnlisten) { _completeUnlisten(); _completeUnlisten = null; }
   if (_cancelledUnlisten) { _cancelledUnlisten(); _cancelledUnlisten = null; }
   if (_errorUnlisten) { _errorUnlisten(); _errorUnlisten = null; }
+  if (_failedUnlisten) { _failedUnlisten(); _failedUnlisten = null; }
+  if (_waitingUnlisten) { _waitingUnlisten(); _waitingUnlisten = null; }
+  if (_restartedUnlisten) { _restartedUnlisten(); _restartedUnlisten = null; }
+  if (_abortedUnlisten) { _abortedUnlisten(); _abortedUnlisten = null; }
+  if (_promptUnlisten) { _promptUnlisten(); _promptUnlisten = null; }
 }
 
 function updateLaunchUI(launching) {
   const launchBtn = document.getElementById('btn-launch');
   const cancelBtn = document.getElementById('btn-cancel');
+  const restartBtn = document.getElementById('btn-restart');
   launchBtn.style.display = launching ? 'none' : '';
   cancelBtn.style.display = launching ? '' : 'none';
+  restartBtn.style.display = launching ? '' : 'none';
 }
 
 function setStatus(text) {
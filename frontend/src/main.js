@@ -1,8 +1,8 @@
-import { loadConfig, getConfig, saveConfig } from './config.js';
-import { renderProfiles, selectProfile, addProfile, getSelectedProfile, getSelectedProfileId, importProfile } from './profiles.js';
-import { renderSteps, addStep } from './steps.js';
-import { startLaunch, cancelLaunch, isLaunching } from './launcher.js';
-import { showSettings, showCloseOnSwitch, showLaunchHistory } from './dialogs.js';
+import { loadConfig, getConfig, saveConfig, setConfig } from './config.js';
+import { renderProfiles, selectProfile, addProfile, getSelectedProfile, getSelectedProfileId, importProfile, importProfileFromUrl, importProfilesFromDir, captureRunningProfile } from './profiles.js';
+import { renderSteps, addStep, setAllStepsEnabled } from './steps.js';
+import { startLaunch, cancelLaunch, restartLaunch, isLaunching, followScheduledLaunch } from './launcher.js';
+import { showSettings, showConfirm, showLaunchHistory } from './dialogs.js';
 import { showStartupPanel } from './startup.js';
 import { toggleProcessPanel } from './processes.js';
 
@@ -10,8 +10,6 @@ const { invoke } = window.__TAURI__.core;
 const { listen } = window.__TAURI__.event;
 const { getCurrentWindow } = window.__TAURI__.window;
 
-let _lastLaunchedProfileId = null;
-
 async function init() {
   try {
     const config = await loadConfig();
@@ -25,7 +23,7 @@ async function init() {
     await listenTrayEvents();
   } catch (err) {
     console.error('Init error:', err);
-    document.getElementById('status-text').textContent = 'Error loading config: ' + err;
+    document.getElementById('status-text').textContent = 'Error loading config: ' + (err?.message ?? err);
   }
 }
 
@@ -42,12 +40,19 @@ function wireEvents() {
   // Add step
   document.getElementById('btn-add-step').addEventListener('click', addStep);
 
+  // Bulk enable/disable steps
+  document.getElementById('btn-enable-all').addEventListener('click', () => setAllStepsEnabled(true));
+  document.getElementById('btn-disable-all').addEventListener('click', () => setAllStepsEnabled(false));
+
   // Launch
   document.getElementById('btn-launch').addEventListener('click', handleLaunch);
 
   // Cancel
   document.getElementById('btn-cancel').addEventListener('click', cancelLaunch);
 
+  // Restart
+  document.getElementById('btn-restart').addEventListener('click', handleRestart);
+
   // Settings
   document.getElementById('btn-settings').addEventListener('click', handleSettings);
 
@@ -60,8 +65,14 @@ function wireEvents() {
   // History
   document.getElementById('btn-history').addEventListener('click', handleHistory);
 
+  // Pause schedules
+  document.getElementById('btn-pause-schedules').addEventListener('click', toggleSchedulerPaused);
+
   // Import profile
   document.getElementById('btn-import-profile').addEventListener('click', importProfile);
+  document.getElementById('btn-import-folder').addEventListener('click', importProfilesFromDir);
+  document.getElementById('btn-import-url').addEventListener('click', importProfileFromUrl);
+  document.getElementById('btn-capture-running').addEventListener('click', captureRunningProfile);
 
   // Global hotkeys
   registerHotkeys();
@@ -73,54 +84,22 @@ async function handleLaunch() {
 
   try {
     const config = getConfig();
-
-    // Close-on-switch: check if previous profile had running processes
-    if (config.settings.close_on_switch && _lastLaunchedProfileId && _lastLaunchedProfileId !== profile.id) {
-      const lastProfile = config.profiles.find(p => p.id === _lastLaunchedProfileId);
-      if (lastProfile) {
-        await handleCloseOnSwitch(lastProfile);
-      }
-    }
-
-    _lastLaunchedProfileId = profile.id;
     const enabledSteps = profile.steps.filter(s => s.enabled);
-    await startLaunch(profile.steps, config.settings.launch_delay_ms || 500);
+    await startLaunch(profile.steps, config.settings.launch_delay_ms || 500, profile.id);
     // Record in history (count enabled steps as launched; errors handled by launcher events)
     recordLaunch(profile.id, profile.name, enabledSteps.length, 0);
   } catch (err) {
     console.error('Launch error:', err);
-    document.getElementById('status-text').textContent = 'Error: ' + err;
+    document.getElementById('status-text').textContent = 'Error: ' + (err?.message ?? err);
   }
 }
 
-async function handleCloseOnSwitch(previousProfile) {
-  // Get process names from previous profile that have process_name set
-  const processNames = previousProfile.steps
-    .filter(s => s.process_name && s.enabled)
-    .map(s => s.process_name);
-
-  if (processNames.length === 0) return;
+async function handleRestart() {
+  const profile = getSelectedProfile();
+  if (!profile) return;
 
-  try {
-    // Check which are actually running
-    const running = await invoke('get_running_processes_for_steps', { processNames });
-    if (running.length === 0) return;
-
-    // Show dialog
-    const toClose = await showCloseOnSwitch(running);
-    if (toClose.length === 0) return;
-
-    // Kill selected processes
-    for (const name of toClose) {
-      try {
-        await invoke('kill_process', { name });
-      } catch (e) {
-        console.error('Failed to kill:', name, e);
-      }
-    }
-  } catch (e) {
-    console.error('Close-on-switch error:', e);
-  }
+  const config = getConfig();
+  await restartLaunch(profile.steps, config.settings.launch_delay_ms || 500, profile.id);
 }
 
 async function handleSettings() {
@@ -128,6 +107,12 @@ async function handleSettings() {
   const result = await showSettings({ ...config.settings });
   if (!result) return;
 
+  if (result === 'reset') {
+    const ok = await showConfirm('Reset settings?', 'All settings go back to their defaults. Profiles are kept.');
+    if (ok) setConfig(await invoke('reset_settings'));
+    return;
+  }
+
   config.settings = result;
   await saveConfig(config);
 }
@@ -144,6 +129,26 @@ async function listenTrayEvents() {
     }, 100);
   });
 
+  await listen('scheduled-launch', async (event) => {
+    const { profile_id, profile_name } = event.payload;
+    selectProfile(profile_id);
+    renderProfiles();
+    await followScheduledLaunch(profile_name);
+  });
+
+  // Set from either the toolbar button or the tray checkbox
+  await listen('scheduler-paused', (event) => showSchedulerPaused(event.payload));
+  showSchedulerPaused(await invoke('get_scheduler_paused'));
+
+  // A schedule toggled from the tray; keep the cached config in step so the
+  // next save doesn't undo it
+  await listen('schedule-changed', (event) => {
+    const updated = event.payload;
+    const profile = getConfig().profiles.find(p => p.id === updated.id);
+    if (profile) profile.schedule = updated.schedule;
+    renderProfiles();
+  });
+
   await listen('tray-show-window', async () => {
     try {
       await invoke('show_window');
@@ -153,6 +158,21 @@ async function listenTrayEvents() {
   });
 }
 
+async function toggleSchedulerPaused() {
+  const btn = document.getElementById('btn-pause-schedules');
+  try {
+    await invoke('set_scheduler_paused', { paused: !btn.classList.contains('active') });
+  } catch (e) {
+    console.error('Pause schedules error:', e);
+  }
+}
+
+function showSchedulerPaused(paused) {
+  const btn = document.getElementById('btn-pause-schedules');
+  btn.classList.toggle('active', paused);
+  btn.title = paused ? 'Schedules paused - click to resume' : 'Pause schedules';
+}
+
 async function handleHistory() {
   const config = getConfig();
   const cleared = await showLaunchHistory(config.launch_history || []);
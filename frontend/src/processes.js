@@ -136,12 +136,9 @@ document.addEventListener('DOMContentLoaded', () => {
 
       try {
         const running = await invoke('get_running_processes_for_steps', { processNames: names });
-        for (const name of running) {
-          try {
-            await invoke('kill_process', { name });
-          } catch (e) {
-            console.error('Kill failed:', name, e);
-          }
+        const results = await invoke('kill_processes', { names: running });
+        for (const [name, result] of results) {
+          if (result.Err) console.error('Kill failed:', name, result.Err);
         }
         setTimeout(refreshProcesses, 500);
       } catch (e) {
@@ -1,4 +1,4 @@
-import { getConfig, saveConfig, newProfile, generateId } from './config.js';
+import { getConfig, saveConfig, loadConfig, newProfile } from './config.js';
 import { renderSteps } from './steps.js';
 import { showProfileEditor } from './dialogs.js';
 
@@ -127,6 +127,8 @@ export function selectProfile(id) {
   const addStepBtn = document.getElementById('btn-add-step');
   launchBtn.disabled = !profile;
   addStepBtn.disabled = !profile;
+  document.getElementById('btn-enable-all').disabled = !profile;
+  document.getElementById('btn-disable-all').disabled = !profile;
 }
 
 function updateContentHeader(profile) {
@@ -187,23 +189,70 @@ export async function importProfile() {
     if (!path) return;
 
     const profile = await invoke('load_profile_file', { path });
-    // Give it a new ID so it doesn't collide
-    profile.id = generateId();
-    // Rename steps IDs too
-    for (const step of profile.steps) {
-      step.id = generateId();
+    await addImportedProfile(profile);
+  } catch (e) {
+    console.error('Import failed:', e);
+  }
+}
+
+export async function importProfileFromUrl() {
+  const { showImportUrlPrompt } = await import('./dialogs.js');
+  let url = '';
+  let error = '';
+  // Re-open the prompt with the error until it works or the user gives up
+  for (;;) {
+    url = await showImportUrlPrompt(url, error);
+    if (!url) return;
+    try {
+      const profile = await invoke('import_profile_from_url', { url });
+      await addImportedProfile(profile);
+      return;
+    } catch (e) {
+      error = e?.message ?? String(e);
     }
+  }
+}
 
-    const config = getConfig();
-    config.profiles.push(profile);
-    await saveConfig(config);
-    selectProfile(profile.id);
+// The backend has already given the profile and its steps fresh IDs
+async function addImportedProfile(profile) {
+  const config = getConfig();
+  config.profiles.push(profile);
+  await saveConfig(config);
+  selectProfile(profile.id);
+  renderProfiles();
+}
+
+export async function importProfilesFromDir() {
+  try {
+    const dir = await invoke('browse_folder');
+    if (!dir) return;
+
+    const { showConfirm } = await import('./dialogs.js');
+    const merge = await showConfirm('Replace existing profiles?', 'Imported profiles that are already in your list (same name, or exported from it) will overwrite them. Cancel to skip them instead.');
+
+    const summary = await invoke('import_profiles_from_dir', { dir, merge });
+    for (const issue of [...summary.skipped, ...summary.failed]) {
+      console.warn(`Import ${issue.file}: ${issue.reason}`);
+    }
+
+    await loadConfig();
     renderProfiles();
   } catch (e) {
     console.error('Import failed:', e);
   }
 }
 
+export async function captureRunningProfile() {
+  try {
+    const name = `Captured ${new Date().toLocaleDateString()}`;
+    const profile = await invoke('capture_running_as_profile', { name });
+    await loadConfig();
+    selectProfile(profile.id);
+  } catch (e) {
+    console.error('Capture failed:', e);
+  }
+}
+
 async function deleteProfile(id) {
   const { showConfirm } = await import('./dialogs.js');
   const ok = await showConfirm('Delete this profile?', 'This cannot be undone.');
@@ -2,10 +2,14 @@ import { getConfig, saveConfig, newStep } from './config.js';
 import { getSelectedProfile, getSelectedProfileId } from './profiles.js';
 import { showStepEditor, showDuplicateNamePrompt } from './dialogs.js';
 
+const { invoke } = window.__TAURI__.core;
+
 export function renderSteps() {
   const profile = getSelectedProfile();
   const list = document.getElementById('step-list');
 
+  showLaunchEstimate(profile);
+
   if (!profile) {
     list.innerHTML = '<div class="empty-state"><span class="empty-state-icon">&#9881;</span><span>Select a profile</span></div>';
     return;
@@ -23,14 +27,14 @@ export function renderSteps() {
     card.className = 'step-card' + (step.enabled ? '' : ' disabled');
     card.dataset.stepId = step.id;
 
-    const badgeLabel = step.type === 'terminal' ? 'CMD' : step.type.toUpperCase();
+    const badgeLabel = step.type === 'terminal' ? 'CMD' : step.type === 'wait_until' ? 'WAIT' : step.type.toUpperCase();
     const detail = getStepDetail(step);
 
     card.innerHTML = `
       <input type="checkbox" class="step-checkbox" ${step.enabled ? 'checked' : ''} title="Enable/disable">
       <span class="step-badge ${step.type}">${badgeLabel}</span>
       <div class="step-info">
-        <div class="step-name">${escapeHtml(step.name || '(unnamed)')}</div>
+        <div class="step-name">${step.icon ? escapeHtml(step.icon) + ' ' : ''}${escapeHtml(step.name || '(unnamed)')}</div>
         <div class="step-detail">${escapeHtml(detail)}</div>
       </div>
       <div class="step-actions">
@@ -38,6 +42,7 @@ export function renderSteps() {
         ${index > 0 ? '<button class="step-action-btn move-up" title="Move up">&#9650;</button>' : ''}
         ${index < profile.steps.length - 1 ? '<button class="step-action-btn move-down" title="Move down">&#9660;</button>' : ''}
         ${index < profile.steps.length - 1 ? '<button class="step-action-btn move-bottom" title="Move to bottom">&#9193;</button>' : ''}
+        ${step.process_name ? '<button class="step-action-btn stop-step" title="Stop this app">&#9632;</button>' : ''}
         <button class="step-action-btn edit-step" title="Edit">&#9998;</button>
         <button class="step-action-btn dup-step" title="Duplicate">&#10697;</button>
         <button class="step-action-btn danger del-step" title="Delete">&#10005;</button>
@@ -76,6 +81,12 @@ export function renderSteps() {
       bottomBtn.addEventListener('click', (e) => { e.stopPropagation(); moveStepToBottom(index); });
     }
 
+    // Stop just this step's app
+    const stopBtn = card.querySelector('.stop-step');
+    if (stopBtn) {
+      stopBtn.addEventListener('click', (e) => { e.stopPropagation(); stopStep(step); });
+    }
+
     // Edit
     card.querySelector('.edit-step').addEventListener('click', (e) => {
       e.stopPropagation();
@@ -96,14 +107,59 @@ export function renderSteps() {
 
     list.appendChild(card);
   });
+
+  markDuplicateSteps(profile.id);
+}
+
+// Label the launch button with roughly how long the launch will take
+async function showLaunchEstimate(profile) {
+  const launchBtn = document.getElementById('btn-launch');
+  launchBtn.textContent = 'Launch';
+  launchBtn.title = '';
+  if (!profile || !profile.steps.some(s => s.enabled)) return;
+
+  try {
+    const defaultDelay = getConfig().settings.launch_delay_ms || 500;
+    const ms = await invoke('estimate_launch_time', { steps: profile.steps, defaultDelay });
+    // Ignore a result for a profile that's no longer selected
+    if (getSelectedProfileId() !== profile.id) return;
+    const secs = Math.max(1, Math.round(ms / 1000));
+    launchBtn.textContent = `Launch (~${secs}s)`;
+    launchBtn.title = `Estimated launch time: about ${secs}s`;
+  } catch (e) {
+    console.error('Launch estimate failed:', e);
+  }
+}
+
+// Flag steps that launch the same target as an earlier step
+async function markDuplicateSteps(profileId) {
+  try {
+    const pairs = await invoke('find_duplicate_steps', { profileId });
+    for (const [firstId, dupId] of pairs) {
+      const card = document.querySelector(`.step-card[data-step-id="${CSS.escape(dupId)}"]`);
+      if (!card) continue;
+      const first = getSelectedProfile()?.steps.find(s => s.id === firstId);
+      card.classList.add('duplicate');
+      card.title = `Launches the same thing as "${first ? first.name : 'another step'}"`;
+    }
+  } catch (e) {
+    console.error('Duplicate check failed:', e);
+  }
 }
 
 function getStepDetail(step) {
   switch (step.type) {
     case 'app': return step.target || '';
     case 'terminal': return step.command || '';
+    case 'shell': return step.command || '';
     case 'folder': return step.target || '';
-    case 'url': return step.target || '';
+    case 'url': {
+      const urls = (step.target || '').split('\n').filter(u => u.trim());
+      return urls.length > 1 ? `${urls[0].trim()} (+${urls.length - 1} more)` : (urls[0] || '').trim();
+    }
+    case 'http': return `${step.method || 'POST'} ${step.target || ''}`;
+    case 'audio': return step.target || '';
+    case 'wait_until': return step.time ? `Until ${step.time}` : '';
     default: return '';
   }
 }
@@ -141,6 +197,31 @@ async function moveStepToBottom(index) {
   renderSteps();
 }
 
+async function stopStep(step) {
+  const status = document.getElementById('status-text');
+  try {
+    await invoke('stop_step', { step });
+    status.textContent = `Stopped ${step.name || step.process_name}`;
+  } catch (e) {
+    status.textContent = `Couldn't stop ${step.name || step.process_name}: ${e?.message ?? e}`;
+  }
+}
+
+export async function setAllStepsEnabled(enabled) {
+  const profileId = getSelectedProfileId();
+  if (!profileId) return;
+
+  try {
+    const updated = await invoke('toggle_all_steps', { profileId, enabled });
+    const config = getConfig();
+    const idx = config.profiles.findIndex(p => p.id === profileId);
+    if (idx !== -1) config.profiles[idx] = updated;
+    renderSteps();
+  } catch (e) {
+    console.error('Toggle steps failed:', e);
+  }
+}
+
 export async function addStep() {
   const profile = getSelectedProfile();
   if (!profile) return;
@@ -0,0 +1,196 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioDeviceKind {
+    Playback,
+    Recording,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDevice {
+    pub id: String,
+    pub name: String,
+    pub kind: AudioDeviceKind,
+    pub is_default: bool,
+}
+
+/// Active playback and recording devices.
+pub fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        core_audio::list_devices().map_err(|e| format!("Failed to list audio devices: {}", e))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("Audio devices can only be listed on Windows".to_string())
+    }
+}
+
+/// Makes the device with this friendly name the default for every role. An
+/// exact (case-insensitive) name wins over a partial match.
+pub fn set_default_device(name: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let devices = list_audio_devices()?;
+        let device = devices
+            .iter()
+            .find(|d| d.name.eq_ignore_ascii_case(name))
+            .or_else(|| {
+                let name = name.to_lowercase();
+                devices
+                    .iter()
+                    .find(|d| d.name.to_lowercase().contains(&name))
+            })
+            .ok_or_else(|| format!("Audio device '{}' not found", name))?;
+
+        core_audio::set_default(&device.id)
+            .map_err(|e| format!("Failed to set default audio device: {}", e))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = name;
+        Err("Setting the audio device is only supported on Windows".to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[allow(non_snake_case)]
+mod core_audio {
+    use super::{AudioDevice, AudioDeviceKind};
+    use crate::com::ComGuard;
+    use std::ffi::c_void;
+    use windows::core::{interface, IUnknown, IUnknown_Vtbl, Interface, GUID, HRESULT, PCWSTR};
+    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::Media::Audio::{
+        eAll, eCapture, eCommunications, eConsole, eMultimedia, eRender, ERole,
+        IMMDeviceEnumerator, IMMEndpoint, MMDeviceEnumerator, DEVICE_STATE_ACTIVE,
+    };
+    use windows::Win32::System::Com::StructuredStorage::{
+        PropVariantClear, PropVariantToStringAlloc,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ,
+    };
+
+    /// Undocumented interface the Sound control panel uses to change the
+    /// default endpoint. Only `SetDefaultEndpoint` is called; the earlier
+    /// methods are declared to keep the vtable layout right.
+    #[interface("f8679f50-850a-41cf-9c72-430f290290c8")]
+    unsafe trait IPolicyConfig: IUnknown {
+        fn GetMixFormat(&self, device: PCWSTR, format: *mut *mut c_void) -> HRESULT;
+        fn GetDeviceFormat(
+            &self,
+            device: PCWSTR,
+            default: i32,
+            format: *mut *mut c_void,
+        ) -> HRESULT;
+        fn ResetDeviceFormat(&self, device: PCWSTR) -> HRESULT;
+        fn SetDeviceFormat(
+            &self,
+            device: PCWSTR,
+            endpoint: *mut c_void,
+            mix: *mut c_void,
+        ) -> HRESULT;
+        fn GetProcessingPeriod(
+            &self,
+            device: PCWSTR,
+            default: i32,
+            default_period: *mut i64,
+            min_period: *mut i64,
+        ) -> HRESULT;
+        fn SetProcessingPeriod(&self, device: PCWSTR, period: *mut i64) -> HRESULT;
+        fn GetShareMode(&self, device: PCWSTR, mode: *mut c_void) -> HRESULT;
+        fn SetShareMode(&self, device: PCWSTR, mode: *mut c_void) -> HRESULT;
+        fn GetPropertyValue(
+            &self,
+            device: PCWSTR,
+            key: *const c_void,
+            value: *mut c_void,
+        ) -> HRESULT;
+        fn SetPropertyValue(
+            &self,
+            device: PCWSTR,
+            key: *const c_void,
+            value: *mut c_void,
+        ) -> HRESULT;
+        fn SetDefaultEndpoint(&self, device: PCWSTR, role: ERole) -> HRESULT;
+        fn SetEndpointVisibility(&self, device: PCWSTR, visible: i32) -> HRESULT;
+    }
+
+    const CLSID_POLICY_CONFIG: GUID = GUID::from_u128(0x870af99c_171d_4f9e_af0d_e63df40c2bc9);
+
+    pub fn list_devices() -> windows::core::Result<Vec<AudioDevice>> {
+        let _com = ComGuard::new(COINIT_MULTITHREADED)?;
+        unsafe {
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+
+            let default_id = |flow| {
+                enumerator
+                    .GetDefaultAudioEndpoint(flow, eConsole)
+                    .and_then(|d| d.GetId())
+                    .map(|id| {
+                        let s = id.to_string().unwrap_or_default();
+                        CoTaskMemFree(Some(id.0 as *const c_void));
+                        s
+                    })
+                    .unwrap_or_default()
+            };
+            let default_playback = default_id(eRender);
+            let default_recording = default_id(eCapture);
+
+            let collection = enumerator.EnumAudioEndpoints(eAll, DEVICE_STATE_ACTIVE)?;
+            let mut devices = Vec::new();
+            for i in 0..collection.GetCount()? {
+                let device = collection.Item(i)?;
+
+                let raw_id = device.GetId()?;
+                let id = raw_id.to_string().unwrap_or_default();
+                CoTaskMemFree(Some(raw_id.0 as *const c_void));
+
+                let mut value = device
+                    .OpenPropertyStore(STGM_READ)?
+                    .GetValue(&PKEY_Device_FriendlyName)?;
+                let raw_name = PropVariantToStringAlloc(&value)?;
+                let name = raw_name.to_string().unwrap_or_default();
+                CoTaskMemFree(Some(raw_name.0 as *const c_void));
+                let _ = PropVariantClear(&mut value);
+
+                let kind = if device.cast::<IMMEndpoint>()?.GetDataFlow()? == eCapture {
+                    AudioDeviceKind::Recording
+                } else {
+                    AudioDeviceKind::Playback
+                };
+                let is_default = match kind {
+                    AudioDeviceKind::Playback => id == default_playback,
+                    AudioDeviceKind::Recording => id == default_recording,
+                };
+
+                devices.push(AudioDevice {
+                    id,
+                    name,
+                    kind,
+                    is_default,
+                });
+            }
+            Ok(devices)
+        }
+    }
+
+    pub fn set_default(device_id: &str) -> windows::core::Result<()> {
+        let _com = ComGuard::new(COINIT_MULTITHREADED)?;
+        let wide: Vec<u16> = device_id.encode_utf16().chain(std::iter::once(0)).collect();
+        unsafe {
+            let policy: IPolicyConfig = CoCreateInstance(&CLSID_POLICY_CONFIG, None, CLSCTX_ALL)?;
+            for role in [eConsole, eMultimedia, eCommunications] {
+                policy
+                    .SetDefaultEndpoint(PCWSTR(wide.as_ptr()), role)
+                    .ok()?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use crate::config::{self, OnFailure, Step};
+use crate::launcher;
+
+/// Command-line flags that change how WorkSwitch starts.
+#[derive(Debug, Default)]
+pub struct StartupFlags {
+    /// `--launch "<profile name>"`: run the profile headless and exit
+    pub launch: Option<String>,
+    /// `--launch-id <id>`: launch a profile in the running instance (jump list)
+    pub launch_id: Option<String>,
+    /// `--list-profiles`: print profile ids and names and exit
+    pub list_profiles: bool,
+    /// `--json`: machine-readable output for `--list-profiles`
+    pub json: bool,
+    /// `--minimized`: start in the tray without showing the window (auto-start)
+    pub minimized: bool,
+}
+
+impl StartupFlags {
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Self {
+        let mut flags = StartupFlags::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--launch" => flags.launch = args.next(),
+                "--launch-id" => flags.launch_id = args.next(),
+                "--list-profiles" => flags.list_profiles = true,
+                "--json" => flags.json = true,
+                "--minimized" => flags.minimized = true,
+                _ => {
+                    if let Some(name) = arg.strip_prefix("--launch=") {
+                        flags.launch = Some(name.to_string());
+                    }
+                }
+            }
+        }
+
+        flags
+    }
+}
+
+/// Hooks stdout up to the console WorkSwitch was started from. Release builds
+/// are GUI-subsystem exes, so without this anything printed is lost. Output
+/// already redirected to a file or pipe is left alone, and it does nothing if
+/// there is no parent console (e.g. started from Explorer).
+pub fn attach_parent_console() {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::Console::{
+            AttachConsole, GetStdHandle, ATTACH_PARENT_PROCESS, STD_OUTPUT_HANDLE,
+        };
+
+        let redirected = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) }.is_ok_and(|h| !h.is_invalid());
+        if !redirected {
+            let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+        }
+    }
+}
+
+/// Launches a profile's enabled steps, with its pre- and post-steps, without
+/// creating the window or tray. Returns the process exit code.
+pub fn launch_profile_headless(name: &str) -> i32 {
+    let cfg = config::load_config();
+    let profile = cfg
+        .profiles
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+        .or_else(|| cfg.profiles.iter().find(|p| p.id == name));
+
+    let Some(profile) = profile else {
+        println!("Profile '{}' not found", name);
+        return 1;
+    };
+
+    // Nobody is here to confirm an unfamiliar URI scheme, so refuse the
+    // profile if any of its steps, hooks included, would open one
+    let untrusted = profile
+        .pre_steps
+        .iter()
+        .chain(&profile.steps)
+        .chain(&profile.post_steps)
+        .filter(|s| s.enabled)
+        .find_map(|s| {
+            launcher::untrusted_schemes(s, &cfg.settings.trusted_uri_schemes)
+                .into_iter()
+                .next()
+                .map(|scheme| (s, scheme))
+        });
+    if let Some((step, scheme)) = untrusted {
+        println!(
+            "Step '{}' opens a {}: URI, which isn't trusted; add the scheme in Settings to allow it",
+            step.name, scheme
+        );
+        return 1;
+    }
+
+    // Same order as a launch from the window: pre-steps, steps, post-steps
+    let mut failed = 0;
+    if !run_hooks_headless("pre", &profile.pre_steps, &mut failed) {
+        println!("Stopping '{}'", profile.name);
+        return 1;
+    }
+
+    let steps: Vec<_> = profile.steps.iter().filter(|s| s.enabled).collect();
+    for (i, step) in steps.iter().enumerate() {
+        if let Some(delay) = step.delay_before {
+            std::thread::sleep(std::time::Duration::from_millis(delay));
+        }
+        match launcher::launch_step(step) {
+            Ok(()) => println!("Launched '{}'", step.name),
+            Err(e) => {
+                println!("Step '{}' failed: {}", step.name, e);
+                failed += 1;
+                // Nobody can answer a prompt here, so it stops like abort
+                if step.on_failure.unwrap_or_default() != OnFailure::Continue {
+                    println!("Stopping '{}'", profile.name);
+                    return 1;
+                }
+            }
+        }
+        if i + 1 < steps.len() || cfg.settings.delay_after_last_step {
+            let delay = step.delay_after.max(cfg.settings.launch_delay_ms);
+            std::thread::sleep(std::time::Duration::from_millis(delay));
+        }
+    }
+
+    // Best-effort, a failure here never stops anything
+    run_hooks_headless("post", &profile.post_steps, &mut failed);
+
+    if failed > 0 {
+        println!(
+            "Profile '{}' launched with {} failed step(s)",
+            profile.name, failed
+        );
+        1
+    } else {
+        println!("Profile '{}' launched", profile.name);
+        0
+    }
+}
+
+/// Runs a profile's enabled pre- or post-steps one at a time, counting
+/// failures in `failed`. Returns false if a required pre-step failed.
+fn run_hooks_headless(phase: &str, steps: &[Step], failed: &mut usize) -> bool {
+    for step in steps.iter().filter(|s| s.enabled) {
+        if let Some(delay) = step.delay_before {
+            std::thread::sleep(std::time::Duration::from_millis(delay));
+        }
+        match launcher::launch_step(step) {
+            Ok(()) => println!("Ran {}-step '{}'", phase, step.name),
+            Err(e) => {
+                println!("{}-step '{}' failed: {}", phase, step.name, e);
+                *failed += 1;
+                if phase == "pre" && step.required.unwrap_or(false) {
+                    return false;
+                }
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(step.delay_after));
+    }
+    true
+}
+
+/// Prints every profile's id and name, one per line or as a JSON array.
+pub fn list_profiles(json: bool) {
+    let cfg = config::load_config();
+
+    if json {
+        let list: Vec<_> = cfg
+            .profiles
+            .iter()
+            .map(|p| serde_json::json!({ "id": p.id, "name": p.name }))
+            .collect();
+        println!("{}", serde_json::Value::Array(list));
+    } else {
+        for profile in &cfg.profiles {
+            println!("{}\t{}", profile.id, profile.name);
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT};
+
+/// Initializes COM for the current thread for as long as it's alive.
+pub struct ComGuard;
+
+impl ComGuard {
+    /// `model` is the threading model the caller's COM objects need, e.g.
+    /// `COINIT_APARTMENTTHREADED` for shell objects.
+    pub fn new(model: COINIT) -> windows::core::Result<Self> {
+        unsafe { CoInitializeEx(None, model).ok()? };
+        Ok(ComGuard)
+    }
+}
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        unsafe { CoUninitialize() };
+    }
+}
@@ -1,15 +1,45 @@
-use crate::config::{self, AppConfig, Profile, Step};
+use crate::audio;
+use crate::conditions;
+use crate::config::{self, AppConfig, OnFailure, Profile, Step};
+use crate::desktop;
 use crate::discovery;
-use crate::launcher;
+use crate::error::WorkSwitchError;
+use crate::housekeeping::{self, CleanupReport};
+use crate::jumplist;
+use crate::launcher::{self, StepHealth, StepPreview};
+use crate::lifecycle::{self, CloseReport};
+use crate::monitor::WatchRegistry;
 use crate::process;
+use crate::scheduler;
+use crate::search;
+use crate::session;
+use crate::system;
 use crate::tray;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tauri::{Emitter, Manager, State};
 
+/// Rough time to spawn one batch of steps, for launch time estimates
+const SPAWN_COST_MS: u64 = 300;
+
+/// Largest profile `import_profile_from_url` will download
+const MAX_PROFILE_BYTES: u64 = 1024 * 1024;
+/// How long `import_profile_from_url` waits for the download
+const IMPORT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// How long `restart_launch` waits for a cancelled launch to finish
+const RESTART_WAIT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How long before a step's timeout its own waits give up
+const STEP_DEADLINE_MARGIN: std::time::Duration = std::time::Duration::from_millis(500);
+
 pub struct LaunchState {
     pub cancel_flag: Arc<AtomicBool>,
     pub is_running: AtomicBool,
+    /// Answers the pending "step failed, keep going?" prompt
+    pub failure_prompt: Mutex<Option<tokio::sync::oneshot::Sender<bool>>>,
 }
 
 impl Default for LaunchState {
@@ -17,43 +47,381 @@ impl Default for LaunchState {
         LaunchState {
             cancel_flag: Arc::new(AtomicBool::new(false)),
             is_running: AtomicBool::new(false),
+            failure_prompt: Mutex::new(None),
+        }
+    }
+}
+
+/// Process names of the steps WorkSwitch has launched, persisted in
+/// session.json so a restart still knows what to close.
+pub struct LastLaunch {
+    processes: Mutex<Vec<String>>,
+}
+
+impl LastLaunch {
+    pub fn load() -> Self {
+        LastLaunch {
+            processes: Mutex::new(session::load_session().launched_processes),
+        }
+    }
+
+    pub fn get_processes(&self) -> Vec<String> {
+        self.processes.lock().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    pub fn set(&self, names: Vec<String>) {
+        if let Ok(mut processes) = self.processes.lock() {
+            *processes = names;
+            Self::persist(&processes);
+        }
+    }
+
+    pub fn add(&self, name: &str) {
+        if name.is_empty() {
+            return;
+        }
+        if let Ok(mut processes) = self.processes.lock() {
+            if !processes.iter().any(|p| p.eq_ignore_ascii_case(name)) {
+                processes.push(name.to_string());
+                Self::persist(&processes);
+            }
+        }
+    }
+
+    fn persist(names: &[String]) {
+        let mut session = session::load_session();
+        session.launched_processes = names.to_vec();
+        if let Err(e) = session::save_session(&session) {
+            eprintln!("Failed to persist launched processes: {}", e);
         }
     }
 }
 
+/// The profile that was launched most recently, persisted in session.json.
+pub struct ActiveProfile {
+    id: Mutex<Option<String>>,
+}
+
+impl ActiveProfile {
+    pub fn load() -> Self {
+        ActiveProfile {
+            id: Mutex::new(session::load_session().active_profile_id),
+        }
+    }
+
+    pub fn get(&self) -> Option<String> {
+        self.id.lock().ok().and_then(|id| id.clone())
+    }
+
+    pub fn set(&self, profile_id: Option<String>) {
+        if let Ok(mut id) = self.id.lock() {
+            *id = profile_id.clone();
+        }
+        let mut session = session::load_session();
+        session.active_profile_id = profile_id;
+        if let Err(e) = session::save_session(&session) {
+            eprintln!("Failed to persist active profile: {}", e);
+        }
+    }
+}
+
+/// Set while schedules are paused from the tray or toolbar. Not persisted,
+/// so schedules are always live after a restart.
+#[derive(Default)]
+pub struct SchedulerState {
+    pub paused: AtomicBool,
+}
+
 #[tauri::command]
-pub fn get_config() -> Result<AppConfig, String> {
+pub fn get_config() -> Result<AppConfig, WorkSwitchError> {
     Ok(config::load_config())
 }
 
 #[tauri::command]
-pub fn save_config(config: AppConfig, app: tauri::AppHandle) -> Result<(), String> {
-    config::save_config(&config)?;
+pub fn save_config(config: AppConfig, app: tauri::AppHandle) -> Result<(), WorkSwitchError> {
+    let previous = config::load_config();
+    config::save_config(&config).map_err(WorkSwitchError::Io)?;
     // Rebuild tray menu to reflect profile changes
     let _ = tray::rebuild_tray_menu(&app, &config);
+    if config.settings.theme != previous.settings.theme {
+        let _ = tray::update_tray_icon(&app, &config);
+    }
+    let profile_names = |cfg: &AppConfig| -> Vec<(String, String)> {
+        cfg.profiles
+            .iter()
+            .map(|p| (p.id.clone(), p.name.clone()))
+            .collect()
+    };
+    if profile_names(&config) != profile_names(&previous) {
+        std::thread::spawn(move || {
+            if let Err(e) = jumplist::rebuild_jump_list(&config) {
+                eprintln!("{}", e);
+            }
+        });
+    }
     Ok(())
 }
 
+/// Puts every setting back to its default, keeping profiles, startup apps and
+/// history. Auto-start mirrors the Run key, which this doesn't touch, so it
+/// keeps its current value.
+#[tauri::command]
+pub fn reset_settings(app: tauri::AppHandle) -> Result<AppConfig, WorkSwitchError> {
+    let mut cfg = config::load_config();
+    let auto_start = cfg.settings.auto_start_with_windows;
+    cfg.settings = AppConfig::default().settings;
+    cfg.settings.auto_start_with_windows = auto_start;
+
+    config::save_config(&cfg).map_err(WorkSwitchError::Io)?;
+    let _ = tray::rebuild_tray_menu(&app, &cfg);
+    let _ = tray::update_tray_icon(&app, &cfg);
+    Ok(cfg)
+}
+
 #[tauri::command]
 pub async fn launch_profile(
     steps: Vec<Step>,
     default_delay: u64,
-    state: State<'_, LaunchState>,
+    profile_id: Option<String>,
+    allow_unsafe: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<(), WorkSwitchError> {
+    let allow_unsafe = allow_unsafe.unwrap_or(false);
+    run_launch(app, steps, default_delay, profile_id, allow_unsafe).await
+}
+
+/// Cancels the running launch, waits for it to wind down, then launches
+/// `steps`. Calling `cancel_launch` then `launch_profile` races the old run
+/// clearing its guard.
+#[tauri::command]
+pub async fn restart_launch(
+    steps: Vec<Step>,
+    default_delay: u64,
+    profile_id: Option<String>,
+    allow_unsafe: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<(), WorkSwitchError> {
+    {
+        let state = app.state::<LaunchState>();
+        if state.is_running.load(Ordering::SeqCst) {
+            state.cancel_flag.store(true, Ordering::SeqCst);
+            let started = std::time::Instant::now();
+            while state.is_running.load(Ordering::SeqCst) {
+                if started.elapsed() >= RESTART_WAIT {
+                    return Err(WorkSwitchError::AlreadyRunning(
+                        "The running launch didn't stop in time".to_string(),
+                    ));
+                }
+                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            }
+        }
+    }
+
+    let _ = app.emit("launch-restarted", ());
+    let allow_unsafe = allow_unsafe.unwrap_or(false);
+    run_launch(app, steps, default_delay, profile_id, allow_unsafe).await
+}
+
+/// Launches a saved profile's enabled steps without going through the
+/// frontend.
+#[tauri::command]
+pub async fn launch_profile_by_id(
+    profile_id: String,
+    app: tauri::AppHandle,
+) -> Result<(), WorkSwitchError> {
+    let cfg = config::load_config();
+    let profile = cfg
+        .profiles
+        .iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| WorkSwitchError::NotFound("Profile not found".to_string()))?;
+    let steps = enabled_steps(&profile.steps);
+
+    let delay = cfg.settings.launch_delay_ms;
+    run_launch(app, steps, delay, Some(profile_id), false).await
+}
+
+/// A step that would open a URI scheme outside the safe list.
+#[derive(Debug, Clone, Serialize)]
+pub struct UntrustedUri {
+    pub step_id: String,
+    pub step_name: String,
+    pub scheme: String,
+}
+
+fn untrusted_uris<'a>(
+    steps: impl IntoIterator<Item = &'a Step>,
+    trusted: &[String],
+) -> Vec<UntrustedUri> {
+    steps
+        .into_iter()
+        .flat_map(|step| {
+            launcher::untrusted_schemes(step, trusted)
+                .into_iter()
+                .map(|scheme| UntrustedUri {
+                    step_id: step.id.clone(),
+                    step_name: step.name.clone(),
+                    scheme,
+                })
+        })
+        .collect()
+}
+
+/// The enabled pre- and post-steps of `profile_id`, if it names a profile.
+fn hook_steps(cfg: &AppConfig, profile_id: Option<&String>) -> (Vec<Step>, Vec<Step>) {
+    cfg.profiles
+        .iter()
+        .find(|p| Some(&p.id) == profile_id)
+        .map(|p| (enabled_steps(&p.pre_steps), enabled_steps(&p.post_steps)))
+        .unwrap_or_default()
+}
+
+/// Steps, including the profile's pre- and post-steps, that would open an
+/// unfamiliar URI scheme, so the UI can confirm before passing
+/// `allow_unsafe` to a launch.
+#[tauri::command]
+pub fn find_untrusted_uris(steps: Vec<Step>, profile_id: Option<String>) -> Vec<UntrustedUri> {
+    let cfg = config::load_config();
+    let (pre_steps, post_steps) = hook_steps(&cfg, profile_id.as_ref());
+    untrusted_uris(
+        pre_steps.iter().chain(&steps).chain(&post_steps),
+        &cfg.settings.trusted_uri_schemes,
+    )
+}
+
+/// Runs a launch under the shared `LaunchState` guard, emitting progress
+/// events as it goes. Unless `allow_unsafe` is set, a step opening a URI
+/// scheme outside the safe and trusted lists refuses the whole launch.
+pub async fn run_launch(
     app: tauri::AppHandle,
-) -> Result<(), String> {
+    steps: Vec<Step>,
+    default_delay: u64,
+    profile_id: Option<String>,
+    allow_unsafe: bool,
+) -> Result<(), WorkSwitchError> {
+    let state = app.state::<LaunchState>();
+    let last_launch = app.state::<LastLaunch>();
+    let active_profile = app.state::<ActiveProfile>();
+    let watches = app.state::<WatchRegistry>();
+
+    let cfg = config::load_config();
+    let (pre_steps, post_steps) = hook_steps(&cfg, profile_id.as_ref());
+    if !allow_unsafe {
+        let untrusted = untrusted_uris(
+            pre_steps.iter().chain(&steps).chain(&post_steps),
+            &cfg.settings.trusted_uri_schemes,
+        );
+        if let Some(first) = untrusted.first() {
+            return Err(WorkSwitchError::Validation(format!(
+                "'{}' opens a {}: URI, which isn't trusted. Launch it from the window to confirm, or add the scheme under trusted URI schemes in Settings",
+                first.step_name, first.scheme
+            )));
+        }
+    }
+
     if state
         .is_running
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
         .is_err()
     {
-        return Err("Launch already in progress".to_string());
+        return Err(WorkSwitchError::AlreadyRunning(
+            "Launch already in progress".to_string(),
+        ));
     }
 
     state.cancel_flag.store(false, Ordering::SeqCst);
     let cancel_flag = state.cancel_flag.clone();
 
+    let step_timeout = cfg.settings.step_timeout_secs;
+
+    // Tear down the outgoing profile when switching profiles
+    let previous_id = active_profile.get();
+    let switching =
+        cfg.settings.close_on_switch && profile_id.is_some() && previous_id != profile_id;
+    let previous = cfg
+        .profiles
+        .iter()
+        .find(|p| switching && Some(&p.id) == previous_id.as_ref());
+    if let Some(previous) = previous {
+        // Close what we launched for it, except apps the new profile also uses
+        let launched = last_launch.get_processes();
+        let to_close: Vec<String> = previous
+            .steps
+            .iter()
+            .map(|s| &s.process_name)
+            .filter(|name| launched.iter().any(|l| l.eq_ignore_ascii_case(name)))
+            .filter(|name| {
+                !steps
+                    .iter()
+                    .any(|s| s.process_name.eq_ignore_ascii_case(name))
+            })
+            .cloned()
+            .collect();
+        for name in &to_close {
+            watches.unwatch(name);
+        }
+
+        let kill_list = previous.kill_on_switch.clone();
+        let closed = tokio::task::spawn_blocking(move || {
+            let report = lifecycle::close_processes(&to_close);
+            for failure in &report.failed {
+                eprintln!(
+                    "Failed to close '{}' on switch: {}",
+                    failure.name, failure.error
+                );
+            }
+
+            for name in &kill_list {
+                let result = if name.ends_with('*') {
+                    process::kill_process_pattern(name).map(|_| ())
+                } else if process::is_running(name) {
+                    process::kill_process(name)
+                } else {
+                    Ok(())
+                };
+                if let Err(e) = result {
+                    eprintln!("Failed to kill '{}' on switch: {}", name, e);
+                }
+            }
+            report.closed
+        })
+        .await
+        .unwrap_or_default();
+
+        last_launch.set(
+            launched
+                .into_iter()
+                .filter(|name| !closed.contains(name))
+                .collect(),
+        );
+    }
+
+    if cfg.settings.launch_on_new_virtual_desktop {
+        match tokio::task::spawn_blocking(desktop::switch_to_new_desktop).await {
+            Ok(Err(e)) => eprintln!("{}", e),
+            Err(e) => eprintln!("Virtual desktop task failed: {}", e),
+            Ok(Ok(())) => {}
+        }
+    }
+
+    match run_hook_steps("pre", &pre_steps, step_timeout, &cancel_flag, &app).await {
+        Ok(true) => {}
+        Ok(false) => {
+            let _ = app.emit("launch-cancelled", ());
+            state.is_running.store(false, Ordering::SeqCst);
+            return Ok(());
+        }
+        Err(e) => {
+            state.is_running.store(false, Ordering::SeqCst);
+            return Err(WorkSwitchError::Spawn(e));
+        }
+    }
+
     let total = steps.len();
-    for (i, step) in steps.iter().enumerate() {
+    let mut current = 0;
+    let batches = launch_batches(&steps);
+    let batch_count = batches.len();
+    for (batch_index, batch) in batches.into_iter().enumerate() {
         // Check cancel
         if cancel_flag.load(Ordering::SeqCst) {
             let _ = app.emit("launch-cancelled", ());
@@ -61,90 +429,718 @@ pub async fn launch_profile(
             return Ok(());
         }
 
-        // Emit progress
+        // Delay before the batch (check cancel every 100ms)
+        let delay = batch
+            .iter()
+            .filter_map(|step| step.delay_before)
+            .max()
+            .unwrap_or(0);
+        if !cancellable_sleep(delay, &cancel_flag).await {
+            let _ = app.emit("launch-cancelled", ());
+            state.is_running.store(false, Ordering::SeqCst);
+            return Ok(());
+        }
+
+        // Spawn every step in the batch so grouped steps launch together
+        let mut handles = Vec::with_capacity(batch.len());
+        for step in &batch {
+            current += 1;
+            let _ = app.emit(
+                "launch-progress",
+                serde_json::json!({
+                    "step_name": step.name,
+                    "current": current,
+                    "total": total,
+                    "phase": "main"
+                }),
+            );
+            if step.step_type == "wait_until" {
+                // Waited here rather than in a task so the step timeout doesn't apply
+                let time = step.time.as_deref().unwrap_or("");
+                match launcher::ms_until(time) {
+                    Ok(ms) => {
+                        if ms > 0 {
+                            let _ = app.emit(
+                                "launch-waiting",
+                                serde_json::json!({
+                                    "step_name": step.name,
+                                    "until": time
+                                }),
+                            );
+                        }
+                        if !cancellable_sleep(ms, &cancel_flag).await {
+                            let _ = app.emit("launch-cancelled", ());
+                            state.is_running.store(false, Ordering::SeqCst);
+                            return Ok(());
+                        }
+                    }
+                    Err(e) => {
+                        let _ = app.emit(
+                            "launch-step-error",
+                            serde_json::json!({
+                                "step_name": step.name,
+                                "error": e
+                            }),
+                        );
+                        match continue_after_failure(&app, step, &e, &cancel_flag).await {
+                            Some(true) => {}
+                            Some(false) => {
+                                state.is_running.store(false, Ordering::SeqCst);
+                                return Err(abort_launch(&app, step, &e));
+                            }
+                            None => {
+                                let _ = app.emit("launch-cancelled", ());
+                                state.is_running.store(false, Ordering::SeqCst);
+                                return Ok(());
+                            }
+                        }
+                    }
+                }
+                handles.push(None);
+                continue;
+            }
+            handles.push(Some(tokio::spawn(launch_with_timeout(
+                step.clone(),
+                step_timeout,
+                cancel_flag.clone(),
+            ))));
+        }
+
+        for (step, handle) in batch.iter().zip(handles) {
+            let Some(handle) = handle else {
+                continue;
+            };
+            let launch_result = match handle.await {
+                Ok(Some(result)) => result,
+                Ok(None) => {
+                    let _ = app.emit("launch-cancelled", ());
+                    state.is_running.store(false, Ordering::SeqCst);
+                    return Ok(());
+                }
+                Err(e) => Err(format!("Task panicked: {}", e)),
+            };
+
+            if let Err(e) = launch_result {
+                eprintln!("Step '{}' failed: {}", step.name, e);
+                let _ = app.emit(
+                    "launch-step-error",
+                    serde_json::json!({
+                        "step_name": step.name,
+                        "error": e,
+                        "log_path": launcher::terminal_log(step).map(|l| l.log)
+                    }),
+                );
+                match continue_after_failure(&app, step, &e, &cancel_flag).await {
+                    Some(true) => {}
+                    Some(false) => {
+                        state.is_running.store(false, Ordering::SeqCst);
+                        return Err(abort_launch(&app, step, &e));
+                    }
+                    None => {
+                        let _ = app.emit("launch-cancelled", ());
+                        state.is_running.store(false, Ordering::SeqCst);
+                        return Ok(());
+                    }
+                }
+            } else {
+                last_launch.add(&step.process_name);
+                watches.watch(step);
+
+                let title = step.wait_for_window.as_deref().unwrap_or("");
+                if !title.is_empty() {
+                    let _ = app.emit(
+                        "launch-waiting",
+                        serde_json::json!({
+                            "step_name": step.name,
+                            "window": title
+                        }),
+                    );
+                    match wait_for_window(title, step_timeout, &cancel_flag).await {
+                        Some(true) => {}
+                        Some(false) => {
+                            // Not fatal, the next steps may not need the window
+                            let _ = app.emit(
+                                "launch-step-error",
+                                serde_json::json!({
+                                    "step_name": step.name,
+                                    "error": format!("Timed out waiting for window '{}'", title)
+                                }),
+                            );
+                        }
+                        None => {
+                            let _ = app.emit("launch-cancelled", ());
+                            state.is_running.store(false, Ordering::SeqCst);
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+
+        // Delay after the batch (check cancel every 100ms)
+        let is_last = batch_index + 1 == batch_count;
+        let delay = if is_last && !cfg.settings.delay_after_last_step {
+            0
+        } else {
+            batch
+                .iter()
+                .map(|step| step.delay_after.max(default_delay))
+                .max()
+                .unwrap_or(0)
+        };
+        if !cancellable_sleep(delay, &cancel_flag).await {
+            let _ = app.emit("launch-cancelled", ());
+            state.is_running.store(false, Ordering::SeqCst);
+            return Ok(());
+        }
+    }
+
+    // Post-steps are best-effort, so only a cancel stops them
+    if let Ok(false) = run_hook_steps("post", &post_steps, step_timeout, &cancel_flag, &app).await {
+        let _ = app.emit("launch-cancelled", ());
+        state.is_running.store(false, Ordering::SeqCst);
+        return Ok(());
+    }
+
+    if let Some(id) = &profile_id {
+        if let Err(e) = session::record_launch(id) {
+            eprintln!("Failed to record launch stats: {}", e);
+        }
+        active_profile.set(profile_id);
+        let _ = tray::rebuild_tray_menu(&app, &config::load_config());
+    }
+
+    if cfg.settings.minimize_after_launch {
+        if let Some(window) = app.get_webview_window("main") {
+            if cfg.settings.minimize_to_tray && tray::tray_available(&app) {
+                let _ = window.hide();
+            } else {
+                let _ = window.minimize();
+            }
+        }
+    }
+
+    let _ = app.emit("launch-complete", ());
+    state.is_running.store(false, Ordering::SeqCst);
+    Ok(())
+}
+
+fn enabled_steps(steps: &[Step]) -> Vec<Step> {
+    steps.iter().filter(|s| s.enabled).cloned().collect()
+}
+
+/// Runs a profile's pre- or post-steps one at a time. Returns `Ok(false)` if
+/// cancelled, or `Err` if a required pre-step failed.
+async fn run_hook_steps(
+    phase: &str,
+    steps: &[Step],
+    step_timeout: u64,
+    cancel_flag: &Arc<AtomicBool>,
+    app: &tauri::AppHandle,
+) -> Result<bool, String> {
+    for (i, step) in steps.iter().enumerate() {
         let _ = app.emit(
             "launch-progress",
             serde_json::json!({
                 "step_name": step.name,
                 "current": i + 1,
-                "total": total
+                "total": steps.len(),
+                "phase": phase
             }),
         );
 
-        // Launch the step in a blocking task with timeout so it can't freeze us
-        let step_clone = step.clone();
-        let step_name = step.name.clone();
-        let cancel = cancel_flag.clone();
+        if !cancellable_sleep(step.delay_before.unwrap_or(0), cancel_flag).await {
+            return Ok(false);
+        }
+        let result = if step.step_type == "wait_until" {
+            // Waited here, like in the main steps, so the step timeout doesn't apply
+            let time = step.time.as_deref().unwrap_or("");
+            match launcher::ms_until(time) {
+                Ok(ms) => {
+                    if ms > 0 {
+                        let _ = app.emit(
+                            "launch-waiting",
+                            serde_json::json!({
+                                "step_name": step.name,
+                                "until": time
+                            }),
+                        );
+                    }
+                    if !cancellable_sleep(ms, cancel_flag).await {
+                        return Ok(false);
+                    }
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            }
+        } else {
+            match launch_with_timeout(step.clone(), step_timeout, cancel_flag.clone()).await {
+                Some(result) => result,
+                None => return Ok(false),
+            }
+        };
+
+        if let Err(e) = result {
+            eprintln!("{}-step '{}' failed: {}", phase, step.name, e);
+            if phase == "pre" && step.required.unwrap_or(false) {
+                return Err(format!("Required step '{}' failed: {}", step.name, e));
+            }
+            let _ = app.emit(
+                "launch-step-error",
+                serde_json::json!({
+                    "step_name": step.name,
+                    "error": e,
+                    "log_path": launcher::terminal_log(step).map(|l| l.log)
+                }),
+            );
+        }
 
-        let launch_result = tokio::select! {
-            result = tokio::task::spawn_blocking(move || {
-                launcher::launch_step(&step_clone)
-            }) => {
-                match result {
-                    Ok(inner) => inner,
-                    Err(e) => Err(format!("Task panicked: {}", e)),
+        if !cancellable_sleep(step.delay_after, cancel_flag).await {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Groups steps into launch batches. Steps sharing a `parallel_group` launch
+/// together at the position of the group's first step; ungrouped steps each
+/// get a batch of their own.
+fn launch_batches(steps: &[Step]) -> Vec<Vec<Step>> {
+    let mut batches: Vec<Vec<Step>> = Vec::new();
+    let mut group_batch: HashMap<u32, usize> = HashMap::new();
+
+    for step in steps {
+        match step.parallel_group {
+            Some(group) => match group_batch.get(&group) {
+                Some(&idx) => batches[idx].push(step.clone()),
+                None => {
+                    group_batch.insert(group, batches.len());
+                    batches.push(vec![step.clone()]);
                 }
+            },
+            None => batches.push(vec![step.clone()]),
+        }
+    }
+
+    batches
+}
+
+/// Milliseconds a launch of the enabled `steps` should take, following the
+/// same batching and delay rules as `run_launch`. Wait-until steps and
+/// `wait_for_window` count as no time since they depend on the clock.
+fn launch_time_estimate(steps: &[Step], default_delay: u64, delay_after_last_step: bool) -> u64 {
+    let batches = launch_batches(&enabled_steps(steps));
+    let batch_count = batches.len();
+
+    let mut total = 0;
+    for (batch_index, batch) in batches.iter().enumerate() {
+        total += batch
+            .iter()
+            .filter_map(|step| step.delay_before)
+            .max()
+            .unwrap_or(0);
+        // Grouped steps spawn concurrently, so a batch costs one spawn
+        if batch.iter().any(|step| step.step_type != "wait_until") {
+            total += SPAWN_COST_MS;
+        }
+
+        let is_last = batch_index + 1 == batch_count;
+        if !is_last || delay_after_last_step {
+            total += batch
+                .iter()
+                .map(|step| step.delay_after.max(default_delay))
+                .max()
+                .unwrap_or(0);
+        }
+    }
+    total
+}
+
+/// Applies a failed step's `on_failure` policy. Returns whether the launch
+/// should go on, or `None` if it was cancelled while the user was prompted.
+async fn continue_after_failure(
+    app: &tauri::AppHandle,
+    step: &Step,
+    error: &str,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Option<bool> {
+    match step.on_failure.unwrap_or_default() {
+        OnFailure::Continue => Some(true),
+        OnFailure::Abort => Some(false),
+        OnFailure::Prompt => {
+            let state = app.state::<LaunchState>();
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            if let Ok(mut prompt) = state.failure_prompt.lock() {
+                *prompt = Some(tx);
             }
-            _ = cancel_wait(cancel) => {
-                let _ = app.emit("launch-cancelled", ());
-                state.is_running.store(false, Ordering::SeqCst);
-                return Ok(());
+
+            // Scheduled launches may be running with the window hidden
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
             }
-            _ = tokio::time::sleep(tokio::time::Duration::from_secs(15)) => {
-                Err("Step timed out after 15s".to_string())
+            let _ = app.emit(
+                "launch-failure-prompt",
+                serde_json::json!({
+                    "step_name": step.name,
+                    "error": error
+                }),
+            );
+
+            let answer = tokio::select! {
+                // A dropped sender means nobody can answer, so stop
+                answer = rx => Some(answer.unwrap_or(false)),
+                _ = cancel_wait(cancel_flag.clone()) => None,
+            };
+            if let Ok(mut prompt) = state.failure_prompt.lock() {
+                *prompt = None;
             }
-        };
+            answer
+        }
+    }
+}
+
+/// Emits `launch-aborted` for the step that stopped the launch and returns
+/// the error `run_launch` reports.
+fn abort_launch(app: &tauri::AppHandle, step: &Step, error: &str) -> WorkSwitchError {
+    let _ = app.emit(
+        "launch-aborted",
+        serde_json::json!({
+            "step_name": step.name,
+            "error": error
+        }),
+    );
+    WorkSwitchError::Spawn(format!(
+        "Launch stopped because '{}' failed: {}",
+        step.name, error
+    ))
+}
+
+/// Launches a step in a blocking task with a timeout so it can't freeze us.
+/// A `timeout_secs` of 0 waits indefinitely. Returns `None` if the launch was
+/// cancelled while waiting.
+async fn launch_with_timeout(
+    step: Step,
+    timeout_secs: u64,
+    cancel: Arc<AtomicBool>,
+) -> Option<Result<(), String>> {
+    let timeout = async {
+        if timeout_secs == 0 {
+            std::future::pending::<()>().await;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(timeout_secs)).await;
+    };
+
+    // Stop the step's own waiting just before the timeout fires, so its
+    // thread doesn't go on to launch something after we've given up on it
+    let deadline = (timeout_secs > 0).then(|| {
+        std::time::Instant::now()
+            + std::time::Duration::from_secs(timeout_secs).saturating_sub(STEP_DEADLINE_MARGIN)
+    });
+    let step_cancel = cancel.clone();
+
+    tokio::select! {
+        result = tokio::task::spawn_blocking(move || {
+            let limits = launcher::LaunchLimits {
+                cancel: Some(&step_cancel),
+                deadline,
+            };
+            launcher::launch_step_within(&step, &limits)
+        }) => {
+            match result {
+                Ok(inner) => Some(inner),
+                Err(e) => Some(Err(format!("Task panicked: {}", e))),
+            }
+        }
+        _ = cancel_wait(cancel) => None,
+        _ = timeout => {
+            Some(Err(format!("Step timed out after {}s", timeout_secs)))
+        }
+    }
+}
+
+/// Sleeps in 100ms slices, returning false as soon as the launch is cancelled.
+async fn cancellable_sleep(ms: u64, cancel: &AtomicBool) -> bool {
+    let mut remaining = ms;
+    while remaining > 0 {
+        if cancel.load(Ordering::SeqCst) {
+            return false;
+        }
+        let sleep_ms = remaining.min(100);
+        tokio::time::sleep(tokio::time::Duration::from_millis(sleep_ms)).await;
+        remaining = remaining.saturating_sub(sleep_ms);
+    }
+    true
+}
+
+/// Polls until a window title contains `title`. Gives up after `timeout_secs`
+/// (0 waits indefinitely). Returns `None` if cancelled, otherwise whether the
+/// window showed up.
+async fn wait_for_window(title: &str, timeout_secs: u64, cancel: &AtomicBool) -> Option<bool> {
+    let started = std::time::Instant::now();
+    loop {
+        let pattern = title.to_string();
+        let found = tokio::task::spawn_blocking(move || process::is_window_open(&pattern, ""))
+            .await
+            .unwrap_or(false);
+        if found {
+            return Some(true);
+        }
+        if timeout_secs > 0 && started.elapsed().as_secs() >= timeout_secs {
+            return Some(false);
+        }
+        if !cancellable_sleep(500, cancel).await {
+            return None;
+        }
+    }
+}
+
+/// Polls the cancel flag every 50ms, resolves when cancelled.
+async fn cancel_wait(flag: Arc<AtomicBool>) {
+    loop {
+        if flag.load(Ordering::SeqCst) {
+            return;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    }
+}
+
+/// Reports steps whose targets no longer resolve. Each check runs in its own
+/// task with a timeout so a slow network drive can't hang the command.
+#[tauri::command]
+pub async fn check_profile_health(profile_id: String) -> Result<Vec<StepHealth>, WorkSwitchError> {
+    let cfg = config::load_config();
+    let profile = cfg
+        .profiles
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| WorkSwitchError::NotFound("Profile not found".to_string()))?;
+
+    let handles: Vec<_> = profile
+        .steps
+        .into_iter()
+        .map(|step| {
+            tokio::spawn(async move {
+                let (step_id, step_name) = (step.id.clone(), step.name.clone());
+                let result = tokio::time::timeout(
+                    tokio::time::Duration::from_secs(5),
+                    tokio::task::spawn_blocking(move || launcher::check_step(&step)),
+                )
+                .await;
+
+                match result {
+                    Ok(Ok(health)) => health,
+                    _ => StepHealth {
+                        step_id,
+                        step_name,
+                        ok: false,
+                        reason: Some("Timed out checking target".to_string()),
+                    },
+                }
+            })
+        })
+        .collect();
+
+    let mut report = Vec::with_capacity(handles.len());
+    for handle in handles {
+        report.push(
+            handle
+                .await
+                .map_err(|e| WorkSwitchError::Other(e.to_string()))?,
+        );
+    }
+    Ok(report)
+}
+
+/// Enables or disables the named steps of a profile and saves, in one go so
+/// a frontend save can't interleave with it.
+#[tauri::command]
+pub fn set_steps_enabled(
+    profile_id: String,
+    step_ids: Vec<String>,
+    enabled: bool,
+    app: tauri::AppHandle,
+) -> Result<Profile, WorkSwitchError> {
+    update_steps_enabled(
+        &profile_id,
+        enabled,
+        |step| step_ids.contains(&step.id),
+        &app,
+    )
+}
+
+#[tauri::command]
+pub fn toggle_all_steps(
+    profile_id: String,
+    enabled: bool,
+    app: tauri::AppHandle,
+) -> Result<Profile, WorkSwitchError> {
+    update_steps_enabled(&profile_id, enabled, |_| true, &app)
+}
+
+fn update_steps_enabled(
+    profile_id: &str,
+    enabled: bool,
+    selected: impl Fn(&Step) -> bool,
+    app: &tauri::AppHandle,
+) -> Result<Profile, WorkSwitchError> {
+    let mut cfg = config::load_config();
+    let profile = cfg
+        .profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| WorkSwitchError::NotFound("Profile not found".to_string()))?;
+    for step in profile.steps.iter_mut().filter(|s| selected(s)) {
+        step.enabled = enabled;
+    }
+    let profile = profile.clone();
+
+    config::save_config(&cfg).map_err(WorkSwitchError::Io)?;
+    let _ = tray::rebuild_tray_menu(app, &cfg);
+    Ok(profile)
+}
+
+/// Pairs of step ids in a profile that launch the same target.
+#[tauri::command]
+pub async fn find_duplicate_steps(
+    profile_id: String,
+) -> Result<Vec<(String, String)>, WorkSwitchError> {
+    let cfg = config::load_config();
+    let profile = cfg
+        .profiles
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| WorkSwitchError::NotFound("Profile not found".to_string()))?;
+
+    tokio::task::spawn_blocking(move || launcher::find_duplicate_steps(&profile.steps))
+        .await
+        .map_err(|e| WorkSwitchError::Other(e.to_string()))
+}
+
+/// The command line a terminal step would run, for debugging quoting.
+#[tauri::command]
+pub fn preview_terminal_command(step: Step) -> Result<String, WorkSwitchError> {
+    launcher::preview_terminal_command(&step).map_err(WorkSwitchError::Validation)
+}
+
+/// Cleans up a pasted path or URL before it's saved into a step.
+#[tauri::command]
+pub fn normalize_target(input: String, step_type: String) -> String {
+    launcher::normalize_target(&input, &step_type)
+}
+
+#[tauri::command]
+pub fn set_scheduler_paused(paused: bool, app: tauri::AppHandle) {
+    scheduler::set_paused(&app, paused);
+}
+
+/// Turns a profile's schedule on or off without saving the whole config.
+#[tauri::command]
+pub fn set_schedule_enabled(
+    profile_id: String,
+    enabled: bool,
+    app: tauri::AppHandle,
+) -> Result<Profile, WorkSwitchError> {
+    scheduler::set_schedule_enabled(&app, &profile_id, enabled)
+}
+
+/// Removes temp files WorkSwitch left behind. Also runs at startup.
+#[tauri::command]
+pub async fn cleanup_temp_artifacts() -> Result<CleanupReport, WorkSwitchError> {
+    tokio::task::spawn_blocking(|| housekeeping::cleanup_temp_artifacts(&config::load_config()))
+        .await
+        .map_err(|e| WorkSwitchError::Other(format!("Cleanup task failed: {}", e)))
+}
+
+#[tauri::command]
+pub fn get_scheduler_paused(state: State<'_, SchedulerState>) -> bool {
+    state.paused.load(Ordering::SeqCst)
+}
+
+/// Profiles that would break if this app (target path or process name) went away.
+#[tauri::command]
+pub async fn find_profiles_using(target_or_process: String) -> Vec<search::ProfileRef> {
+    tokio::task::spawn_blocking(move || {
+        search::find_profiles_using(&config::load_config(), &target_or_process)
+    })
+    .await
+    .unwrap_or_default()
+}
 
-        if let Err(e) = launch_result {
-            eprintln!("Step '{}' failed: {}", step_name, e);
-            // Emit error but continue
-            let _ = app.emit(
-                "launch-step-error",
-                serde_json::json!({
-                    "step_name": step_name,
-                    "error": e
-                }),
-            );
-        }
+#[tauri::command]
+pub fn quick_search(query: String) -> Vec<search::QuickResult> {
+    search::quick_search(&config::load_config(), &query)
+}
 
-        // Delay after step (check cancel every 100ms)
-        let delay = step.delay_after.max(default_delay);
-        if delay > 0 {
-            let mut remaining = delay;
-            while remaining > 0 {
-                if cancel_flag.load(Ordering::SeqCst) {
-                    let _ = app.emit("launch-cancelled", ());
-                    state.is_running.store(false, Ordering::SeqCst);
-                    return Ok(());
-                }
-                let sleep_ms = remaining.min(100);
-                tokio::time::sleep(tokio::time::Duration::from_millis(sleep_ms)).await;
-                remaining = remaining.saturating_sub(sleep_ms);
-            }
-        }
-    }
+/// A profile's launch stats alongside its description.
+#[derive(Debug, Serialize)]
+pub struct ProfileStatsEntry {
+    #[serde(flatten)]
+    pub stats: session::ProfileStats,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
 
-    let _ = app.emit("launch-complete", ());
-    state.is_running.store(false, Ordering::SeqCst);
-    Ok(())
+#[tauri::command]
+pub fn get_profile_stats() -> HashMap<String, ProfileStatsEntry> {
+    let cfg = config::load_config();
+    session::load_session()
+        .profile_stats
+        .into_iter()
+        .map(|(id, stats)| {
+            let description = cfg
+                .profiles
+                .iter()
+                .find(|p| p.id == id)
+                .map(|p| p.description.clone())
+                .unwrap_or_default();
+            (id, ProfileStatsEntry { stats, description })
+        })
+        .collect()
 }
 
-/// Polls the cancel flag every 50ms, resolves when cancelled.
-async fn cancel_wait(flag: Arc<AtomicBool>) {
-    loop {
-        if flag.load(Ordering::SeqCst) {
-            return;
-        }
-        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-    }
+#[tauri::command]
+pub async fn preview_profile(steps: Vec<Step>) -> Vec<StepPreview> {
+    tokio::task::spawn_blocking(move || steps.iter().map(launcher::preview_step).collect())
+        .await
+        .unwrap_or_default()
+}
+
+/// Estimated milliseconds a launch of `steps` will take.
+#[tauri::command]
+pub fn estimate_launch_time(steps: Vec<Step>, default_delay: u64) -> u64 {
+    let delay_after_last_step = config::load_config().settings.delay_after_last_step;
+    launch_time_estimate(&steps, default_delay, delay_after_last_step)
 }
 
 #[tauri::command]
-pub fn cancel_launch(state: State<'_, LaunchState>) -> Result<(), String> {
+pub fn cancel_launch(state: State<'_, LaunchState>) -> Result<(), WorkSwitchError> {
     state.cancel_flag.store(true, Ordering::SeqCst);
     Ok(())
 }
 
+/// Answers the prompt shown when a step with `on_failure: prompt` fails.
+#[tauri::command]
+pub fn resolve_failure_prompt(
+    continue_launch: bool,
+    state: State<'_, LaunchState>,
+) -> Result<(), WorkSwitchError> {
+    let sender = state
+        .failure_prompt
+        .lock()
+        .ok()
+        .and_then(|mut prompt| prompt.take());
+    match sender {
+        Some(tx) => {
+            let _ = tx.send(continue_launch);
+            Ok(())
+        }
+        None => Err(WorkSwitchError::NotFound(
+            "No launch is waiting on a failed step".to_string(),
+        )),
+    }
+}
+
 #[tauri::command]
 pub async fn is_process_running(name: String) -> bool {
     let result = tokio::time::timeout(
@@ -160,18 +1156,207 @@ pub async fn is_process_running(name: String) -> bool {
 }
 
 #[tauri::command]
-pub async fn kill_process(name: String) -> Result<(), String> {
+pub async fn kill_process(
+    name: String,
+    watches: State<'_, WatchRegistry>,
+) -> Result<(), WorkSwitchError> {
+    watches.unwatch(&name);
     let result = tokio::time::timeout(
         tokio::time::Duration::from_secs(5),
-        tokio::task::spawn_blocking(move || process::kill_process(&name)),
+        tokio::task::spawn_blocking(move || {
+            if name.ends_with('*') {
+                process::kill_process_pattern(&name).map(|_| ())
+            } else {
+                process::kill_process(&name)
+            }
+        }),
+    )
+    .await;
+
+    match result {
+        Ok(Ok(inner)) => inner.map_err(WorkSwitchError::Other),
+        Ok(Err(e)) => Err(WorkSwitchError::Other(format!("Kill task failed: {}", e))),
+        Err(_) => Err(WorkSwitchError::Timeout("Kill timed out".to_string())),
+    }
+}
+
+/// Kills several processes at once with a result per name.
+#[tauri::command]
+pub async fn kill_processes(
+    names: Vec<String>,
+    watches: State<'_, WatchRegistry>,
+) -> Result<Vec<(String, Result<(), String>)>, WorkSwitchError> {
+    for name in &names {
+        watches.unwatch(name);
+    }
+    let result = tokio::time::timeout(
+        tokio::time::Duration::from_secs(10),
+        tokio::task::spawn_blocking(move || process::kill_processes(&names)),
     )
     .await;
 
     match result {
-        Ok(Ok(inner)) => inner,
-        Ok(Err(e)) => Err(format!("Kill task failed: {}", e)),
-        Err(_) => Err("Kill timed out".to_string()),
+        Ok(Ok(results)) => Ok(results),
+        Ok(Err(e)) => Err(WorkSwitchError::Other(format!("Kill task failed: {}", e))),
+        Err(_) => Err(WorkSwitchError::Timeout("Kill timed out".to_string())),
+    }
+}
+
+#[tauri::command]
+pub async fn close_all_launched(
+    force: bool,
+    last_launch: State<'_, LastLaunch>,
+    watches: State<'_, WatchRegistry>,
+) -> Result<CloseReport, WorkSwitchError> {
+    let cfg = config::load_config();
+    if !force && !cfg.settings.close_on_exit {
+        return Err(WorkSwitchError::Validation(
+            "Closing launched apps is disabled in settings".to_string(),
+        ));
+    }
+    watches.clear();
+
+    let names = last_launch.get_processes();
+    let report = tokio::task::spawn_blocking(move || lifecycle::close_processes(&names))
+        .await
+        .map_err(|e| WorkSwitchError::Other(format!("Close task failed: {}", e)))?;
+
+    // Stop tracking whatever was closed; keep failures so a retry can pick them up
+    let remaining = last_launch
+        .get_processes()
+        .into_iter()
+        .filter(|name| !report.closed.contains(name))
+        .collect();
+    last_launch.set(remaining);
+
+    Ok(report)
+}
+
+/// Replaces the tracked launched processes, e.g. after the user closed some
+/// apps by hand.
+#[tauri::command]
+pub fn set_last_launch_processes(names: Vec<String>, last_launch: State<'_, LastLaunch>) {
+    let mut deduped: Vec<String> = Vec::new();
+    for name in names {
+        if !name.is_empty() && !deduped.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+            deduped.push(name);
+        }
+    }
+    last_launch.set(deduped);
+}
+
+#[tauri::command]
+pub async fn close_profile(
+    profile_id: String,
+    last_launch: State<'_, LastLaunch>,
+    active_profile: State<'_, ActiveProfile>,
+    watches: State<'_, WatchRegistry>,
+    app: tauri::AppHandle,
+) -> Result<CloseReport, WorkSwitchError> {
+    let cfg = config::load_config();
+    let profile = cfg
+        .profiles
+        .iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| WorkSwitchError::NotFound("Profile not found".to_string()))?;
+
+    let names: Vec<String> = profile
+        .steps
+        .iter()
+        .filter(|s| !s.process_name.is_empty())
+        .map(|s| s.process_name.clone())
+        .collect();
+    for name in &names {
+        watches.unwatch(name);
+    }
+    let child_patterns: Vec<String> = profile
+        .steps
+        .iter()
+        .filter(|s| s.match_children.unwrap_or(false) && !s.process_name.is_empty())
+        .map(|s| {
+            let name = s.process_name.to_lowercase();
+            format!("{}*", name.strip_suffix(".exe").unwrap_or(&name))
+        })
+        .collect();
+    let report = tokio::task::spawn_blocking(move || {
+        let mut report = lifecycle::close_processes(&names);
+        // Sweep up helper processes the graceful close left behind
+        for pattern in &child_patterns {
+            match process::kill_process_pattern(pattern) {
+                Ok(killed) => report.closed.extend(killed),
+                Err(e) => eprintln!("Failed to kill '{}': {}", pattern, e),
+            }
+        }
+        report
+    })
+    .await
+    .map_err(|e| WorkSwitchError::Other(format!("Close task failed: {}", e)))?;
+
+    let remaining = last_launch
+        .get_processes()
+        .into_iter()
+        .filter(|name| !report.closed.contains(name))
+        .collect();
+    last_launch.set(remaining);
+
+    if active_profile.get().as_deref() == Some(profile_id.as_str()) {
+        active_profile.set(None);
+        let _ = tray::rebuild_tray_menu(&app, &cfg);
+    }
+
+    Ok(report)
+}
+
+/// Gracefully closes one step's process (and its helpers if the step matches
+/// children). A step that isn't running counts as stopped.
+#[tauri::command]
+pub async fn stop_step(
+    step: Step,
+    last_launch: State<'_, LastLaunch>,
+    watches: State<'_, WatchRegistry>,
+) -> Result<(), WorkSwitchError> {
+    let name = step.process_name.trim().to_string();
+    if name.is_empty() {
+        return Err(WorkSwitchError::Validation(format!(
+            "'{}' has no process name to stop",
+            step.name
+        )));
+    }
+    watches.unwatch(&name);
+
+    let child_pattern = step.match_children.unwrap_or(false).then(|| {
+        let lower = name.to_lowercase();
+        format!("{}*", lower.strip_suffix(".exe").unwrap_or(&lower))
+    });
+    let close_name = name.clone();
+    let report = tokio::task::spawn_blocking(move || {
+        let report = lifecycle::close_processes(std::slice::from_ref(&close_name));
+        if let Some(pattern) = child_pattern {
+            if let Err(e) = process::kill_process_pattern(&pattern) {
+                eprintln!("Failed to kill '{}': {}", pattern, e);
+            }
+        }
+        report
+    })
+    .await
+    .map_err(|e| WorkSwitchError::Other(format!("Stop task failed: {}", e)))?;
+
+    if let Some(failure) = report.failed.into_iter().next() {
+        return Err(WorkSwitchError::Other(failure.error));
     }
+
+    let remaining = last_launch
+        .get_processes()
+        .into_iter()
+        .filter(|p| !p.eq_ignore_ascii_case(&name))
+        .collect();
+    last_launch.set(remaining);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_active_profile(active_profile: State<'_, ActiveProfile>) -> Option<String> {
+    active_profile.get()
 }
 
 #[tauri::command]
@@ -195,7 +1380,62 @@ pub async fn get_running_processes_for_steps(process_names: Vec<String>) -> Vec<
 }
 
 #[tauri::command]
-pub async fn browse_file(app: tauri::AppHandle) -> Result<Option<String>, String> {
+pub async fn get_open_windows() -> Vec<process::WindowInfo> {
+    tokio::task::spawn_blocking(process::get_open_windows)
+        .await
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub async fn get_process_stats(names: Vec<String>) -> Vec<process::ProcessStat> {
+    let result = tokio::time::timeout(
+        tokio::time::Duration::from_secs(10),
+        tokio::task::spawn_blocking(move || process::get_process_stats(&names)),
+    )
+    .await;
+
+    match result {
+        Ok(Ok(stats)) => stats,
+        _ => vec![], // timeout or error = return empty
+    }
+}
+
+#[tauri::command]
+pub async fn get_current_ssid() -> Result<Option<String>, WorkSwitchError> {
+    tokio::task::spawn_blocking(conditions::current_ssid)
+        .await
+        .map_err(|e| WorkSwitchError::Other(e.to_string()))
+}
+
+#[tauri::command]
+pub fn get_system_info(app: tauri::AppHandle) -> system::SystemInfo {
+    system::system_info(app.package_info().version.to_string())
+}
+
+/// Bytes free on the volume holding `path`.
+#[tauri::command]
+pub fn get_free_space(path: String) -> Result<u64, WorkSwitchError> {
+    if !std::path::Path::new(&path).exists() {
+        return Err(WorkSwitchError::NotFound(format!("{} not found", path)));
+    }
+    system::free_space(&path).map_err(WorkSwitchError::Io)
+}
+
+#[tauri::command]
+pub fn get_power_status() -> conditions::PowerStatus {
+    conditions::power_status()
+}
+
+#[tauri::command]
+pub async fn list_audio_devices() -> Result<Vec<audio::AudioDevice>, WorkSwitchError> {
+    let devices = tokio::task::spawn_blocking(audio::list_audio_devices)
+        .await
+        .map_err(|e| WorkSwitchError::Other(e.to_string()))??;
+    Ok(devices)
+}
+
+#[tauri::command]
+pub async fn browse_file(app: tauri::AppHandle) -> Result<Option<String>, WorkSwitchError> {
     use tauri_plugin_dialog::DialogExt;
 
     let file = app
@@ -209,7 +1449,7 @@ pub async fn browse_file(app: tauri::AppHandle) -> Result<Option<String>, String
 }
 
 #[tauri::command]
-pub async fn browse_folder(app: tauri::AppHandle) -> Result<Option<String>, String> {
+pub async fn browse_folder(app: tauri::AppHandle) -> Result<Option<String>, WorkSwitchError> {
     use tauri_plugin_dialog::DialogExt;
 
     let folder = app.dialog().file().blocking_pick_folder();
@@ -219,13 +1459,31 @@ pub async fn browse_folder(app: tauri::AppHandle) -> Result<Option<String>, Stri
 
 #[tauri::command]
 pub async fn scan_apps() -> Vec<discovery::DiscoveredApp> {
-    tokio::task::spawn_blocking(|| discovery::scan_all())
+    tokio::task::spawn_blocking(discovery::scan_all)
         .await
         .unwrap_or_default()
 }
 
+/// Discovered apps keyed by source (steam, epic, ...), each group sorted by
+/// name. Built on `scan_all`, so its cross-source dedup still applies.
 #[tauri::command]
-pub fn set_auto_start(enabled: bool) -> Result<(), String> {
+pub async fn scan_apps_grouped() -> HashMap<String, Vec<discovery::DiscoveredApp>> {
+    let apps = tokio::task::spawn_blocking(discovery::scan_all)
+        .await
+        .unwrap_or_default();
+
+    let mut groups: HashMap<String, Vec<discovery::DiscoveredApp>> = HashMap::new();
+    for app in apps {
+        groups.entry(app.source.clone()).or_default().push(app);
+    }
+    for group in groups.values_mut() {
+        group.sort_by_key(|app| app.name.to_lowercase());
+    }
+    groups
+}
+
+#[tauri::command]
+pub fn set_auto_start(enabled: bool) -> Result<(), WorkSwitchError> {
     #[cfg(target_os = "windows")]
     {
         use winreg::enums::*;
@@ -237,14 +1495,15 @@ pub fn set_auto_start(enabled: bool) -> Result<(), String> {
                 r"SOFTWARE\Microsoft\Windows\CurrentVersion\Run",
                 KEY_WRITE,
             )
-            .map_err(|e| format!("Failed to open Run key: {}", e))?;
+            .map_err(|e| WorkSwitchError::Io(format!("Failed to open Run key: {}", e)))?;
 
         if enabled {
             let exe_path = std::env::current_exe()
-                .map_err(|e| format!("Failed to get exe path: {}", e))?;
+                .map_err(|e| WorkSwitchError::Io(format!("Failed to get exe path: {}", e)))?;
+            // Quoted so paths with spaces (e.g. Program Files) parse correctly
             run_key
-                .set_value("WorkSwitch", &exe_path.to_string_lossy().to_string())
-                .map_err(|e| format!("Failed to set registry value: {}", e))?;
+                .set_value("WorkSwitch", &auto_start_command(&exe_path))
+                .map_err(|e| WorkSwitchError::Io(format!("Failed to set registry value: {}", e)))?;
         } else {
             // Ignore error if value doesn't exist
             let _ = run_key.delete_value("WorkSwitch");
@@ -254,11 +1513,54 @@ pub fn set_auto_start(enabled: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// Whether the Run key entry exists and still points at this exe. A stale
+/// entry (e.g. left over from an old install location) reports false.
+#[tauri::command]
+pub fn get_auto_start() -> Result<bool, WorkSwitchError> {
+    let mut enabled = false;
+
+    #[cfg(target_os = "windows")]
+    {
+        use winreg::enums::*;
+        use winreg::RegKey;
+
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let run_key = hkcu
+            .open_subkey(r"SOFTWARE\Microsoft\Windows\CurrentVersion\Run")
+            .map_err(|e| WorkSwitchError::Io(format!("Failed to open Run key: {}", e)))?;
+        let exe_path = std::env::current_exe()
+            .map_err(|e| WorkSwitchError::Io(format!("Failed to get exe path: {}", e)))?;
+
+        if let Ok(value) = run_key.get_value::<String, _>("WorkSwitch") {
+            enabled = registered_exe(&value).eq_ignore_ascii_case(&exe_path.to_string_lossy());
+        }
+    }
+
+    Ok(enabled)
+}
+
+/// The command line written to the Run key. `--minimized` keeps the window
+/// from flashing up at login.
+#[cfg(any(target_os = "windows", test))]
+fn auto_start_command(exe_path: &std::path::Path) -> String {
+    format!("\"{}\" --minimized", exe_path.display())
+}
+
+/// The exe path from a Run key command line, without quotes or arguments.
+#[cfg(any(target_os = "windows", test))]
+fn registered_exe(command: &str) -> &str {
+    let command = command.trim();
+    match command.strip_prefix('"') {
+        Some(rest) => rest.split('"').next().unwrap_or(rest),
+        None => command,
+    }
+}
+
 #[tauri::command]
 pub async fn browse_save_profile(
     default_name: String,
     app: tauri::AppHandle,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, WorkSwitchError> {
     use tauri_plugin_dialog::DialogExt;
 
     let file = app
@@ -272,7 +1574,9 @@ pub async fn browse_save_profile(
 }
 
 #[tauri::command]
-pub async fn browse_import_profile(app: tauri::AppHandle) -> Result<Option<String>, String> {
+pub async fn browse_import_profile(
+    app: tauri::AppHandle,
+) -> Result<Option<String>, WorkSwitchError> {
     use tauri_plugin_dialog::DialogExt;
 
     let file = app
@@ -285,36 +1589,340 @@ pub async fn browse_import_profile(app: tauri::AppHandle) -> Result<Option<Strin
 }
 
 #[tauri::command]
-pub fn export_profile(profile_id: String) -> Result<String, String> {
+pub fn export_profile(profile_id: String) -> Result<String, WorkSwitchError> {
     let cfg = config::load_config();
     let profile = cfg
         .profiles
         .iter()
         .find(|p| p.id == profile_id)
-        .ok_or_else(|| "Profile not found".to_string())?;
+        .ok_or_else(|| WorkSwitchError::NotFound("Profile not found".to_string()))?;
+
+    serde_json::to_string_pretty(profile).map_err(|e| WorkSwitchError::Other(e.to_string()))
+}
+
+/// Parses an exported profile and gives it, and every step, a fresh id so
+/// importing the same file twice can't produce clashing profiles.
+#[tauri::command]
+pub fn import_profile(json: String) -> Result<Profile, WorkSwitchError> {
+    let mut profile = parse_profile(&json)?;
+    regenerate_ids(&mut profile);
+    Ok(profile)
+}
+
+fn parse_profile(json: &str) -> Result<Profile, WorkSwitchError> {
+    serde_json::from_str(json)
+        .map_err(|e| WorkSwitchError::Validation(format!("Invalid profile JSON: {}", e)))
+}
 
-    serde_json::to_string_pretty(profile).map_err(|e| e.to_string())
+fn regenerate_ids(profile: &mut Profile) {
+    profile.id = uuid::Uuid::new_v4().to_string();
+    for step in profile
+        .pre_steps
+        .iter_mut()
+        .chain(&mut profile.steps)
+        .chain(&mut profile.post_steps)
+    {
+        step.id = uuid::Uuid::new_v4().to_string();
+    }
 }
 
+/// Downloads a shared profile (e.g. a raw gist) and imports it through
+/// `import_profile`, so it gets fresh ids too. Only HTTPS is allowed, and
+/// the body is capped at `MAX_PROFILE_BYTES`.
 #[tauri::command]
-pub fn import_profile(json: String) -> Result<Profile, String> {
-    serde_json::from_str(&json).map_err(|e| format!("Invalid profile JSON: {}", e))
+pub async fn import_profile_from_url(url: String) -> Result<Profile, WorkSwitchError> {
+    let json = tokio::task::spawn_blocking(move || fetch_profile_json(&url))
+        .await
+        .map_err(|e| WorkSwitchError::Other(format!("Download task failed: {}", e)))??;
+    import_profile(json)
+}
+
+fn fetch_profile_json(url: &str) -> Result<String, WorkSwitchError> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| WorkSwitchError::Validation(format!("Invalid URL: {}", e)))?;
+    if parsed.scheme() != "https" {
+        return Err(WorkSwitchError::Validation(
+            "Only https:// URLs can be imported".to_string(),
+        ));
+    }
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(IMPORT_TIMEOUT)
+        .build()
+        .map_err(|e| WorkSwitchError::Other(format!("Failed to create HTTP client: {}", e)))?;
+    let response = client.get(parsed.clone()).send().map_err(|e| {
+        if e.is_timeout() {
+            WorkSwitchError::Timeout(format!("Timed out downloading {}", parsed))
+        } else {
+            WorkSwitchError::Other(format!("Failed to download {}: {}", parsed, e))
+        }
+    })?;
+
+    // A redirect could have dropped us onto plain http
+    if response.url().scheme() != "https" {
+        return Err(WorkSwitchError::Validation(
+            "The URL redirected to a non-https address".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(WorkSwitchError::Other(format!(
+            "{} returned {}",
+            parsed,
+            response.status()
+        )));
+    }
+
+    // Raw gists are served as text/plain; HTML means the page, not the file
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    if content_type.starts_with("text/html") {
+        return Err(WorkSwitchError::Validation(
+            "The URL returned a web page; use the link to the raw JSON file".to_string(),
+        ));
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_PROFILE_BYTES)
+    {
+        return Err(profile_too_large());
+    }
+
+    // Content-Length can be missing or wrong, so cap what we actually read
+    let mut body = Vec::new();
+    std::io::Read::read_to_end(
+        &mut std::io::Read::take(response, MAX_PROFILE_BYTES + 1),
+        &mut body,
+    )
+    .map_err(|e| WorkSwitchError::Io(format!("Failed to read response: {}", e)))?;
+    if body.len() as u64 > MAX_PROFILE_BYTES {
+        return Err(profile_too_large());
+    }
+
+    String::from_utf8(body)
+        .map_err(|_| WorkSwitchError::Validation("The profile is not valid UTF-8".to_string()))
+}
+
+fn profile_too_large() -> WorkSwitchError {
+    WorkSwitchError::Validation(format!(
+        "The profile is larger than {} KB",
+        MAX_PROFILE_BYTES / 1024
+    ))
 }
 
 #[tauri::command]
-pub fn save_profile_file(profile_id: String, path: String) -> Result<(), String> {
+pub fn save_profile_file(profile_id: String, path: String) -> Result<(), WorkSwitchError> {
     let json = export_profile(profile_id)?;
-    std::fs::write(&path, &json).map_err(|e| format!("Failed to write file: {}", e))
+    std::fs::write(&path, &json)
+        .map_err(|e| WorkSwitchError::Io(format!("Failed to write file: {}", e)))
 }
 
 #[tauri::command]
-pub fn load_profile_file(path: String) -> Result<Profile, String> {
-    let json = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+pub fn load_profile_file(path: String) -> Result<Profile, WorkSwitchError> {
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| WorkSwitchError::Io(format!("Failed to read file: {}", e)))?;
     import_profile(json)
 }
 
+#[derive(Debug, Default, Serialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: Vec<ImportIssue>,
+    pub failed: Vec<ImportIssue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportIssue {
+    pub file: String,
+    pub reason: String,
+}
+
+/// Imports every `*.json` profile in `dir`, each through the same parsing and
+/// id regeneration as `import_profile`. A profile that's already in the
+/// config, by its exported id or by name, replaces it when `merge` is set
+/// and is skipped otherwise, so importing a folder twice adds nothing new.
+#[tauri::command]
+pub fn import_profiles_from_dir(
+    dir: String,
+    merge: bool,
+    app: tauri::AppHandle,
+) -> Result<ImportSummary, WorkSwitchError> {
+    let entries = std::fs::read_dir(&dir)
+        .map_err(|e| WorkSwitchError::Io(format!("Failed to read directory: {}", e)))?;
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        })
+        .collect();
+    paths.sort();
+
+    let mut cfg = config::load_config();
+    let mut summary = ImportSummary::default();
+    // Exported ids seen so far, mapped to the id the profile has in `cfg`
+    let mut imported_ids: HashMap<String, String> = HashMap::new();
+
+    for path in paths {
+        let file = path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| WorkSwitchError::Io(format!("Failed to read file: {}", e)))
+            .and_then(|json| parse_profile(&json));
+        let mut profile = match parsed {
+            Ok(profile) => profile,
+            Err(e) => {
+                summary.failed.push(ImportIssue {
+                    file,
+                    reason: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let exported_id = std::mem::take(&mut profile.id);
+        let existing_id = imported_ids.get(&exported_id).cloned().or_else(|| {
+            cfg.profiles
+                .iter()
+                .find(|p| p.id == exported_id || p.name == profile.name)
+                .map(|p| p.id.clone())
+        });
+        regenerate_ids(&mut profile);
+
+        match existing_id {
+            Some(id) if merge => {
+                // Keep the existing id so the tray, hotkeys and history still match
+                profile.id = id.clone();
+                if let Some(existing) = cfg.profiles.iter_mut().find(|p| p.id == id) {
+                    *existing = profile;
+                }
+                imported_ids.insert(exported_id, id);
+            }
+            Some(_) => {
+                summary.skipped.push(ImportIssue {
+                    file,
+                    reason: format!("'{}' is already in your profiles", profile.name),
+                });
+                continue;
+            }
+            None => {
+                imported_ids.insert(exported_id, profile.id.clone());
+                cfg.profiles.push(profile);
+            }
+        }
+        summary.imported += 1;
+    }
+
+    if summary.imported > 0 {
+        config::save_config(&cfg).map_err(WorkSwitchError::Io)?;
+        refresh_profile_menus(&app, &cfg);
+    }
+    Ok(summary)
+}
+
+/// Rebuilds the tray menu, and the jump list in the background, after a
+/// command added profiles to the config itself.
+fn refresh_profile_menus(app: &tauri::AppHandle, cfg: &AppConfig) {
+    let _ = tray::rebuild_tray_menu(app, cfg);
+    let cfg = cfg.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = jumplist::rebuild_jump_list(&cfg) {
+            eprintln!("{}", e);
+        }
+    });
+}
+
+/// Saves the apps that currently have a window open as a new draft profile.
+/// Apps found by discovery get its launch target; the rest only get a process
+/// name and start disabled until a target is filled in.
+#[tauri::command]
+pub async fn capture_running_as_profile(
+    name: String,
+    app: tauri::AppHandle,
+) -> Result<Profile, WorkSwitchError> {
+    let steps = tokio::task::spawn_blocking(capture_running_steps)
+        .await
+        .map_err(|e| WorkSwitchError::Other(e.to_string()))?;
+    if steps.is_empty() {
+        return Err(WorkSwitchError::NotFound(
+            "No running apps to capture".to_string(),
+        ));
+    }
+
+    let profile = Profile {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        description: "Captured from running apps".to_string(),
+        steps,
+        tags: vec![],
+        hotkey: String::new(),
+        schedule: None,
+        kill_on_switch: vec![],
+        pre_steps: vec![],
+        post_steps: vec![],
+    };
+
+    let mut cfg = config::load_config();
+    cfg.profiles.push(profile.clone());
+    config::save_config(&cfg).map_err(WorkSwitchError::Io)?;
+    refresh_profile_menus(&app, &cfg);
+    Ok(profile)
+}
+
+fn capture_running_steps() -> Vec<Step> {
+    let own_exe = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.file_name().map(|f| f.to_string_lossy().to_lowercase()))
+        .unwrap_or_default();
+    let discovered = discovery::scan_all();
+
+    let mut seen = std::collections::HashSet::new();
+    let mut steps = Vec::new();
+    for window in process::get_open_windows() {
+        let process_name = window.process_name;
+        if process::is_critical(&process_name)
+            || process_name == own_exe
+            || !seen.insert(process_name.clone())
+        {
+            continue;
+        }
+
+        let found = discovered
+            .iter()
+            .find(|d| d.process_name.eq_ignore_ascii_case(&process_name));
+        let name = match found {
+            Some(d) => d.name.clone(),
+            None => process_name
+                .strip_suffix(".exe")
+                .unwrap_or(&process_name)
+                .to_string(),
+        };
+        steps.push(Step {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            step_type: "app".to_string(),
+            enabled: found.is_some(),
+            delay_after: 500,
+            target: found.map(|d| d.target.clone()),
+            check_running: Some(true),
+            process_name,
+            ..Default::default()
+        });
+    }
+    steps
+}
+
 #[tauri::command]
-pub fn show_window(app: tauri::AppHandle) -> Result<(), String> {
+pub fn show_window(app: tauri::AppHandle) -> Result<(), WorkSwitchError> {
     if let Some(window) = app.get_webview_window("main") {
         let _ = window.show();
         let _ = window.unminimize();
@@ -322,3 +1930,73 @@ pub fn show_window(app: tauri::AppHandle) -> Result<(), String> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_start_command_round_trips_paths_with_spaces() {
+        let exe = std::path::Path::new(r"C:\Program Files\WorkSwitch\WorkSwitch.exe");
+        let command = auto_start_command(exe);
+        assert_eq!(
+            command,
+            r#""C:\Program Files\WorkSwitch\WorkSwitch.exe" --minimized"#
+        );
+        assert_eq!(registered_exe(&command), exe.to_string_lossy());
+    }
+
+    #[test]
+    fn registered_exe_accepts_unquoted_values() {
+        assert_eq!(
+            registered_exe(r"C:\Tools\WorkSwitch.exe"),
+            r"C:\Tools\WorkSwitch.exe"
+        );
+    }
+
+    fn timed_step(delay_after: u64, parallel_group: Option<u32>) -> Step {
+        Step {
+            step_type: "app".to_string(),
+            enabled: true,
+            delay_after,
+            parallel_group,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn estimate_adds_spawn_cost_and_delays_between_steps() {
+        let steps = [timed_step(1000, None), timed_step(500, None)];
+        let spawns = 2 * SPAWN_COST_MS;
+        assert_eq!(launch_time_estimate(&steps, 0, false), spawns + 1000);
+        assert_eq!(launch_time_estimate(&steps, 0, true), spawns + 1500);
+    }
+
+    #[test]
+    fn estimate_uses_default_delay_when_longer() {
+        let steps = [timed_step(100, None), timed_step(100, None)];
+        let spawns = 2 * SPAWN_COST_MS;
+        assert_eq!(launch_time_estimate(&steps, 400, false), spawns + 400);
+    }
+
+    #[test]
+    fn estimate_counts_a_parallel_group_once() {
+        let mut first = timed_step(200, Some(1));
+        first.delay_before = Some(100);
+        let mut second = timed_step(800, Some(1));
+        second.delay_before = Some(400);
+        assert_eq!(
+            launch_time_estimate(&[first, second], 0, true),
+            400 + SPAWN_COST_MS + 800
+        );
+    }
+
+    #[test]
+    fn estimate_skips_disabled_and_wait_until_steps() {
+        let mut disabled = timed_step(1000, None);
+        disabled.enabled = false;
+        let mut wait = timed_step(0, None);
+        wait.step_type = "wait_until".to_string();
+        assert_eq!(launch_time_estimate(&[disabled, wait], 0, false), 0);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::config::{LaunchCondition, PowerSource};
+use serde::Serialize;
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Returns `Err` with the reason a scheduled launch should be skipped.
+pub fn check(condition: &LaunchCondition) -> Result<(), String> {
+    match condition {
+        LaunchCondition::WifiSsid(wanted) => match current_ssid() {
+            Some(ssid) if ssid == *wanted => Ok(()),
+            Some(ssid) => Err(format!("on Wi-Fi '{}', not '{}'", ssid, wanted)),
+            None => Err(format!("not connected to Wi-Fi '{}'", wanted)),
+        },
+        LaunchCondition::PowerSource(PowerSource::Any) => Ok(()),
+        LaunchCondition::PowerSource(PowerSource::AcOnly) => {
+            // Unknown counts as plugged in so desktops aren't blocked
+            if power_status().ac_online == Some(false) {
+                Err("running on battery".to_string())
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+/// SSID of the connected Wi-Fi network, if any.
+pub fn current_ssid() -> Option<String> {
+    #[cfg(target_os = "windows")]
+    {
+        let output = Command::new("netsh")
+            .args(["wlan", "show", "interfaces"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .ok()?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            // Format: "    SSID                   : MyNetwork" (BSSID is a separate line)
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            if key.trim() == "SSID" {
+                let ssid = value.trim();
+                if !ssid.is_empty() {
+                    return Some(ssid.to_string());
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PowerStatus {
+    /// None if Windows can't tell
+    pub ac_online: Option<bool>,
+    pub battery_percent: Option<u8>,
+}
+
+pub fn power_status() -> PowerStatus {
+    let mut power = PowerStatus::default();
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+        let mut status = SYSTEM_POWER_STATUS::default();
+        if unsafe { GetSystemPowerStatus(&mut status) }.is_ok() {
+            // 255 means unknown for both fields
+            power.ac_online = match status.ACLineStatus {
+                0 => Some(false),
+                1 => Some(true),
+                _ => None,
+            };
+            power.battery_percent =
+                (status.BatteryLifePercent <= 100).then_some(status.BatteryLifePercent);
+        }
+    }
+
+    power
+}
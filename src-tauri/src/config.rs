@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -26,16 +28,51 @@ pub struct LaunchRecord {
 pub struct Settings {
     #[serde(default = "default_theme")]
     pub theme: String,
+    /// Minimum gap after each step; a step's longer `delay_after` wins
     #[serde(default = "default_delay")]
     pub launch_delay_ms: u64,
+    /// Also wait after the final step (off: the launch ends right away)
+    #[serde(default)]
+    pub delay_after_last_step: bool,
     #[serde(default)]
     pub start_minimized: bool,
     #[serde(default = "default_true")]
     pub close_on_switch: bool,
     #[serde(default = "default_true")]
     pub minimize_to_tray: bool,
+    /// Hide (or minimize) the window once a launch completes
+    #[serde(default)]
+    pub minimize_after_launch: bool,
+    /// Open a fresh virtual desktop before launching (Windows only)
+    #[serde(default)]
+    pub launch_on_new_virtual_desktop: bool,
+    /// Tee the output of terminal steps that don't stay open to a log file
+    #[serde(default)]
+    pub log_terminal_output: bool,
     #[serde(default)]
     pub auto_start_with_windows: bool,
+    #[serde(default)]
+    pub close_on_exit: bool,
+    #[serde(default = "default_true")]
+    pub confirm_close_on_exit: bool,
+    /// Seconds a step may take to spawn before it's failed; 0 disables
+    #[serde(default = "default_step_timeout")]
+    pub step_timeout_secs: u64,
+    /// URI schemes the user allowed on top of the built-in safe list
+    #[serde(default)]
+    pub trusted_uri_schemes: Vec<String>,
+    #[serde(default)]
+    pub tray_layout: TrayLayout,
+}
+
+/// Where "Show WorkSwitch" sits in the tray menu relative to the profiles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayLayout {
+    #[default]
+    ShowFirst,
+    /// Profiles at the top for one-click launching, Show below them
+    ProfilesFirst,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,17 +88,83 @@ pub struct Profile {
     pub hotkey: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schedule: Option<Schedule>,
+    /// Process names to terminate when switching away from this profile
+    #[serde(default)]
+    pub kill_on_switch: Vec<String>,
+    /// Run one at a time before `steps`; a failed `required` one aborts
+    #[serde(default)]
+    pub pre_steps: Vec<Step>,
+    /// Run one at a time after `steps`, best-effort
+    #[serde(default)]
+    pub post_steps: Vec<Step>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Schedule {
     pub enabled: bool,
+    #[serde(default)]
     pub time: String,
     #[serde(default)]
     pub days: Vec<u8>,
+    #[serde(default)]
+    pub trigger: ScheduleTrigger,
+    /// Checked right before a scheduled launch fires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<LaunchCondition>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchCondition {
+    /// Only launch while connected to this Wi-Fi network
+    WifiSsid(String),
+    PowerSource(PowerSource),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    /// Skip the launch while running on battery
+    AcOnly,
+    Any,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleTrigger {
+    /// Fire at `time` on the selected days
+    #[default]
+    Time,
+    /// Fire when the user unlocks the session on the selected days
+    OnUnlock,
+}
+
+/// What a launch does when a step fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnFailure {
+    /// Report the error and launch the next step
+    #[default]
+    Continue,
+    /// Stop the launch
+    Abort,
+    /// Ask the user whether to keep going
+    Prompt,
+}
+
+/// Another account to run a step under. The password is never stored here;
+/// Windows asks for it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunAsUser {
+    /// `user`, `DOMAIN\user` or `user@domain`
+    pub username: String,
+    /// Let Windows save the password in Credential Manager after the first
+    /// prompt (`runas /savecred`). Off unless the user opts in.
+    #[serde(default)]
+    pub save_credentials: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Step {
     pub id: String,
     pub name: String,
@@ -73,11 +176,48 @@ pub struct Step {
     pub delay_after: u64,
     #[serde(default)]
     pub process_name: String,
-    // App/folder/url fields
+    /// Steps sharing a group number launch concurrently
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_group: Option<u32>,
+    /// Also close processes whose name starts with this step's (helpers like
+    /// `chrome_crashpad.exe`) when the profile is closed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_children: Option<bool>,
+    /// Milliseconds to wait before launching the step
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_before: Option<u64>,
+    /// A failed required pre-step aborts the whole launch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+    /// What the launch does if this step fails; continues if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<OnFailure>,
+    /// Emoji shown next to the step name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Launch an app or terminal step as another Windows user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_as_user: Option<RunAsUser>,
+    // App/folder/url/http fields
     #[serde(skip_serializing_if = "Option::is_none")]
     pub target: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub check_running: Option<bool>,
+    /// For store URIs like `steam://`, start the store client and wait for it
+    /// before firing the URI, so a cold start doesn't swallow the launch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_client_first: Option<bool>,
+    /// Treat the app as running if a window title contains this, for apps
+    /// whose process name is shared (Electron, Chrome PWAs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_title_match: Option<String>,
+    /// After launching, wait until a window title contains this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_for_window: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watch: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart_on_crash: Option<bool>,
     // Terminal fields
     #[serde(skip_serializing_if = "Option::is_none")]
     pub command: Option<String>,
@@ -85,6 +225,17 @@ pub struct Step {
     pub working_dir: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub keep_open: Option<bool>,
+    // Wait fields
+    /// Clock time (HH:MM) a wait_until step holds the launch until
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    // HTTP fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<BTreeMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
 }
 
 fn default_theme() -> String {
@@ -95,6 +246,10 @@ fn default_delay() -> u64 {
     500
 }
 
+fn default_step_timeout() -> u64 {
+    15
+}
+
 fn default_true() -> bool {
     true
 }
@@ -105,10 +260,19 @@ impl Default for AppConfig {
             settings: Settings {
                 theme: default_theme(),
                 launch_delay_ms: 500,
+                delay_after_last_step: false,
                 start_minimized: false,
                 close_on_switch: true,
                 minimize_to_tray: true,
+                minimize_after_launch: false,
+                launch_on_new_virtual_desktop: false,
+                log_terminal_output: false,
                 auto_start_with_windows: false,
+                close_on_exit: false,
+                confirm_close_on_exit: true,
+                step_timeout_secs: default_step_timeout(),
+                trusted_uri_schemes: vec![],
+                tray_layout: TrayLayout::default(),
             },
             profiles: vec![],
             startup_apps: vec![],
@@ -117,7 +281,15 @@ impl Default for AppConfig {
     }
 }
 
+/// Where config.json lives (session.json sits beside it). In order:
+/// `WORKSWITCH_CONFIG` if its folder exists and is writable, an existing
+/// config next to the exe, one in the project root for dev builds, and
+/// finally a new one next to the exe.
 pub fn config_path() -> PathBuf {
+    if let Some(path) = env_config_path() {
+        return path;
+    }
+
     // Look next to the executable first, then fall back to current dir
     if let Ok(exe) = std::env::current_exe() {
         if let Some(dir) = exe.parent() {
@@ -145,6 +317,38 @@ pub fn config_path() -> PathBuf {
         .unwrap_or_else(|| PathBuf::from("config.json"))
 }
 
+/// `WORKSWITCH_CONFIG`, for tests and running separate setups side by side.
+/// Checked once per run, since every load and save asks for the path.
+fn env_config_path() -> Option<PathBuf> {
+    static ENV_CONFIG: OnceLock<Option<PathBuf>> = OnceLock::new();
+    ENV_CONFIG.get_or_init(resolve_env_config_path).clone()
+}
+
+fn resolve_env_config_path() -> Option<PathBuf> {
+    let value = std::env::var("WORKSWITCH_CONFIG").ok()?;
+    if value.trim().is_empty() {
+        return None;
+    }
+
+    let path = PathBuf::from(value.trim());
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    // The read-only attribute means nothing for folders on Windows, so probe
+    let probe = dir.join(".workswitch-write-test");
+    let writable = dir.is_dir() && fs::write(&probe, b"").is_ok();
+    let _ = fs::remove_file(&probe);
+    if !writable {
+        eprintln!(
+            "Ignoring WORKSWITCH_CONFIG: '{}' is not a writable folder",
+            dir.display()
+        );
+        return None;
+    }
+    Some(path)
+}
+
 pub fn load_config() -> AppConfig {
     let path = config_path();
     match fs::read_to_string(&path) {
@@ -165,8 +369,126 @@ pub fn save_config(config: &AppConfig) -> Result<(), String> {
 
     // Atomic write: write to temp file, then rename
     let tmp_path = path.with_extension("json.tmp");
-    fs::write(&tmp_path, &json).map_err(|e| format!("Failed to write config: {}", e))?;
-    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to rename config: {}", e))?;
+    retry_io(|| fs::write(&tmp_path, &json))
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    retry_io(|| fs::rename(&tmp_path, &path))
+        .map_err(|e| format!("Failed to rename config: {}", e))?;
 
     Ok(())
 }
+
+/// Tries a file operation up to three times, sleeping 100ms then 200ms in
+/// between. Antivirus and search indexers briefly lock freshly written files
+/// on Windows, failing the write or rename with "access denied" or a sharing
+/// violation. Any other error is returned straight away.
+pub fn retry_io<T>(mut op: impl FnMut() -> std::io::Result<T>) -> std::io::Result<T> {
+    const ATTEMPTS: u64 = 3;
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt >= ATTEMPTS || !is_transient_lock(&e) => return Err(e),
+            Err(_) => {
+                std::thread::sleep(std::time::Duration::from_millis(100 * attempt));
+                attempt += 1;
+            }
+        }
+    }
+}
+
+fn is_transient_lock(e: &std::io::Error) -> bool {
+    // 32 is ERROR_SHARING_VIOLATION, 33 is ERROR_LOCK_VIOLATION
+    e.kind() == std::io::ErrorKind::PermissionDenied
+        || (cfg!(target_os = "windows") && matches!(e.raw_os_error(), Some(32 | 33)))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Points `WORKSWITCH_CONFIG` at a throwaway folder, the same one for
+    /// every test in the run since the path is only resolved once.
+    pub(crate) fn isolated_config_path() -> PathBuf {
+        static PATH: OnceLock<PathBuf> = OnceLock::new();
+        PATH.get_or_init(|| {
+            let dir = std::env::temp_dir().join(format!("workswitch-test-{}", std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("config.json");
+            std::env::set_var("WORKSWITCH_CONFIG", &path);
+            path
+        })
+        .clone()
+    }
+
+    #[test]
+    fn env_var_wins() {
+        let path = isolated_config_path();
+        assert_eq!(config_path(), path);
+    }
+
+    #[test]
+    fn retry_io_gives_up_on_other_errors() {
+        let mut calls = 0;
+        let result: std::io::Result<()> = retry_io(|| {
+            calls += 1;
+            Err(std::io::ErrorKind::NotFound.into())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn retry_io_retries_access_denied() {
+        let mut calls = 0;
+        let result = retry_io(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(std::io::ErrorKind::PermissionDenied.into())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn schedule_survives_save_and_load() {
+        isolated_config_path();
+        let mut config = AppConfig::default();
+        config.profiles.push(Profile {
+            id: "scheduled".to_string(),
+            name: "Morning".to_string(),
+            description: String::new(),
+            steps: Vec::new(),
+            tags: Vec::new(),
+            hotkey: String::new(),
+            schedule: Some(Schedule {
+                enabled: true,
+                time: "08:30".to_string(),
+                days: vec![1, 2, 3, 4, 5],
+                trigger: ScheduleTrigger::OnUnlock,
+                condition: Some(LaunchCondition::PowerSource(PowerSource::AcOnly)),
+            }),
+            kill_on_switch: Vec::new(),
+            pre_steps: Vec::new(),
+            post_steps: Vec::new(),
+        });
+        save_config(&config).unwrap();
+
+        let loaded = load_config();
+        let profile = loaded
+            .profiles
+            .iter()
+            .find(|p| p.id == "scheduled")
+            .unwrap();
+        let schedule = profile.schedule.as_ref().unwrap();
+        assert!(schedule.enabled);
+        assert_eq!(schedule.time, "08:30");
+        assert_eq!(schedule.days, [1, 2, 3, 4, 5]);
+        assert_eq!(schedule.trigger, ScheduleTrigger::OnUnlock);
+        assert_eq!(
+            schedule.condition,
+            Some(LaunchCondition::PowerSource(PowerSource::AcOnly))
+        );
+    }
+}
@@ -0,0 +1,81 @@
+/// Creates a new virtual desktop and switches to it, so apps launched next
+/// open there. The COM interface that manages desktops is undocumented and
+/// changes between Windows builds, so this presses Win+Ctrl+D instead.
+pub fn switch_to_new_desktop() -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        keyboard::new_desktop_shortcut()?;
+        // Let the switch animation finish before windows start opening
+        std::thread::sleep(std::time::Duration::from_millis(400));
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        eprintln!("Virtual desktops are only supported on Windows; launching on the current one");
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod keyboard {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+        VIRTUAL_KEY, VK_D, VK_LCONTROL, VK_LWIN,
+    };
+
+    fn key(vk: VIRTUAL_KEY, up: bool) -> INPUT {
+        let flags = if up {
+            KEYEVENTF_KEYUP
+        } else {
+            KEYBD_EVENT_FLAGS(0)
+        };
+        INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: vk,
+                    dwFlags: flags,
+                    ..Default::default()
+                },
+            },
+        }
+    }
+
+    fn send(inputs: &[INPUT]) -> usize {
+        unsafe { SendInput(inputs, std::mem::size_of::<INPUT>() as i32) as usize }
+    }
+
+    pub fn new_desktop_shortcut() -> Result<(), String> {
+        // (key, is key-up) for Win+Ctrl+D
+        let keys = [
+            (VK_LWIN, false),
+            (VK_LCONTROL, false),
+            (VK_D, false),
+            (VK_D, true),
+            (VK_LCONTROL, true),
+            (VK_LWIN, true),
+        ];
+        let inputs: Vec<INPUT> = keys.iter().map(|&(vk, up)| key(vk, up)).collect();
+        let sent = send(&inputs);
+        if sent == inputs.len() {
+            return Ok(());
+        }
+
+        // Release whatever went down before the failure so the user isn't
+        // left with Win or Ctrl held
+        let mut held = Vec::new();
+        for &(vk, up) in &keys[..sent.min(keys.len())] {
+            if up {
+                held.retain(|&h| h != vk);
+            } else {
+                held.push(vk);
+            }
+        }
+        let releases: Vec<INPUT> = held.iter().rev().map(|&vk| key(vk, true)).collect();
+        if !releases.is_empty() {
+            send(&releases);
+        }
+        Err("Failed to send the new desktop shortcut".to_string())
+    }
+}
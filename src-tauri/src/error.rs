@@ -0,0 +1,41 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Error returned by commands. Serialized as `{ "kind": ..., "message": ... }`
+/// so the frontend can branch on the kind; `Display` gives the plain message.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum WorkSwitchError {
+    NotFound(String),
+    Io(String),
+    Spawn(String),
+    Timeout(String),
+    AlreadyRunning(String),
+    Validation(String),
+    /// Anything that doesn't fit the kinds above
+    Other(String),
+}
+
+impl fmt::Display for WorkSwitchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            WorkSwitchError::NotFound(m)
+            | WorkSwitchError::Io(m)
+            | WorkSwitchError::Spawn(m)
+            | WorkSwitchError::Timeout(m)
+            | WorkSwitchError::AlreadyRunning(m)
+            | WorkSwitchError::Validation(m)
+            | WorkSwitchError::Other(m) => m,
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for WorkSwitchError {}
+
+/// Helpers below the command layer still return plain strings
+impl From<String> for WorkSwitchError {
+    fn from(message: String) -> Self {
+        WorkSwitchError::Other(message)
+    }
+}
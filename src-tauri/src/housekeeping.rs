@@ -0,0 +1,93 @@
+use crate::config::{self, AppConfig};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Prefix of every file WorkSwitch puts in the system temp folder
+const TEMP_PREFIX: &str = "workswitch-";
+/// A `.json.tmp` younger than this may be a save still in progress
+const TMP_MIN_AGE: Duration = Duration::from_secs(60);
+/// Other leftovers are only removed once they're at least a day old
+const MIN_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanupReport {
+    pub removed: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Removes files WorkSwitch left behind:
+/// - `config.json.tmp` / `session.json.tmp` from an interrupted save
+/// - terminal log wrappers (`logs/<step id>.cmd`) of steps that no longer exist
+/// - `workswitch-*` files in the system temp folder
+///
+/// Logs themselves are kept, and nothing without one of these names is touched.
+pub fn cleanup_temp_artifacts(config: &AppConfig) -> CleanupReport {
+    let mut report = CleanupReport::default();
+    let config_path = config::config_path();
+
+    for name in ["config.json.tmp", "session.json.tmp"] {
+        remove_if_old(&config_path.with_file_name(name), TMP_MIN_AGE, &mut report);
+    }
+
+    let step_ids: HashSet<&str> = config
+        .profiles
+        .iter()
+        .flat_map(|p| p.steps.iter().chain(&p.pre_steps).chain(&p.post_steps))
+        .chain(&config.startup_apps)
+        .map(|s| s.id.as_str())
+        .collect();
+    if let Ok(entries) = std::fs::read_dir(config_path.with_file_name("logs")) {
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            let orphaned = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("cmd"))
+                && path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|id| !step_ids.contains(id));
+            if orphaned {
+                remove_if_old(&path, MIN_AGE, &mut report);
+            }
+        }
+    }
+
+    if let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) {
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            let ours = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.to_ascii_lowercase().starts_with(TEMP_PREFIX));
+            if ours {
+                remove_if_old(&path, MIN_AGE, &mut report);
+            }
+        }
+    }
+
+    report
+}
+
+/// Deletes `path` if it's a file last modified at least `min_age` ago.
+fn remove_if_old(path: &Path, min_age: Duration, report: &mut CleanupReport) {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return;
+    };
+    let age = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .unwrap_or_default();
+    if !metadata.is_file() || age < min_age {
+        return;
+    }
+
+    let display = path.to_string_lossy().to_string();
+    match std::fs::remove_file(path) {
+        Ok(()) => report.removed.push(display),
+        Err(e) => {
+            eprintln!("Failed to remove {}: {}", display, e);
+            report.failed.push(display);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::config::{AppConfig, Profile};
+use crate::session;
+
+/// Windows shows about ten tasks before the list gets cut off
+const MAX_TASKS: usize = 10;
+
+/// Profiles for the taskbar jump list, most recently launched first.
+fn jump_list_profiles(config: &AppConfig) -> Vec<&Profile> {
+    let stats = session::load_session().profile_stats;
+    let mut profiles: Vec<&Profile> = config.profiles.iter().collect();
+    // Stable sort keeps config order for profiles never launched
+    profiles.sort_by_key(|p| std::cmp::Reverse(stats.get(&p.id).and_then(|s| s.last_launched)));
+    profiles.truncate(MAX_TASKS);
+    profiles
+}
+
+/// Replaces the taskbar jump list tasks with one `--launch-id <id>` entry
+/// per profile.
+pub fn rebuild_jump_list(config: &AppConfig) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        let tasks: Vec<(&str, &str)> = jump_list_profiles(config)
+            .iter()
+            .map(|p| (p.id.as_str(), p.name.as_str()))
+            .collect();
+        shell::set_tasks(&exe.to_string_lossy(), &tasks)
+            .map_err(|e| format!("Failed to update jump list: {}", e))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = jump_list_profiles(config);
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod shell {
+    use crate::com::ComGuard;
+    use windows::core::{Interface, HSTRING};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+    use windows::Win32::System::Com::StructuredStorage::PROPVARIANT;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+    use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+    use windows::Win32::UI::Shell::{
+        DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW, ShellLink,
+    };
+
+    /// `profiles` holds the id and name of each profile to add.
+    pub fn set_tasks(exe: &str, profiles: &[(&str, &str)]) -> windows::core::Result<()> {
+        let _com = ComGuard::new(COINIT_APARTMENTTHREADED)?;
+        unsafe {
+            let list: ICustomDestinationList =
+                CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+            let mut min_slots = 0u32;
+            let _removed: IObjectArray = list.BeginList(&mut min_slots)?;
+
+            let tasks: IObjectCollection =
+                CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+            for (id, name) in profiles {
+                tasks.AddObject(&task_link(exe, id, name)?)?;
+            }
+
+            list.AddUserTasks(&tasks.cast::<IObjectArray>()?)?;
+            list.CommitList()
+        }
+    }
+
+    /// A shell link that runs `exe --launch-id <id>`, titled with the name. The
+    /// running instance picks the id up through the single-instance handler.
+    unsafe fn task_link(exe: &str, id: &str, name: &str) -> windows::core::Result<IShellLinkW> {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(&HSTRING::from(exe))?;
+        link.SetArguments(&HSTRING::from(format!("--launch-id {}", id)))?;
+        link.SetIconLocation(&HSTRING::from(exe), 0)?;
+        link.SetDescription(&HSTRING::from(format!("Launch {}", name)))?;
+
+        let props: IPropertyStore = link.cast()?;
+        props.SetValue(&PKEY_Title, &PROPVARIANT::from(name))?;
+        props.Commit()?;
+        Ok(link)
+    }
+}
@@ -1,6 +1,12 @@
-use crate::config::Step;
+use crate::audio;
+use crate::config::{self, RunAsUser, Step};
 use crate::process;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -12,42 +18,630 @@ const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+/// How often a shell step checks whether its command has exited
+const SHELL_POLL: std::time::Duration = std::time::Duration::from_millis(100);
+/// How long to keep reading stderr after a shell command exits, in case a
+/// process it started in the background still holds the pipe
+const SHELL_STDERR_GRACE: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Store clients that game URIs go through: URI scheme, client process, and
+/// a URI that just opens the client
+const STORE_CLIENTS: &[(&str, &str, &str)] = &[
+    ("steam:", "steam.exe", "steam://open/main"),
+    (
+        "com.epicgames.launcher:",
+        "EpicGamesLauncher.exe",
+        "com.epicgames.launcher://",
+    ),
+    ("goggalaxy:", "GalaxyClient.exe", "goggalaxy://"),
+    ("uplay:", "upc.exe", "uplay://"),
+];
+/// Extra time for a freshly started client to log in and accept URIs
+const CLIENT_SETTLE: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// URI schemes that open without confirmation: the web, mail, and the store,
+/// chat and editor clients profiles commonly open. Anything else (`file:`,
+/// `javascript:`, `ms-msdt:`, ...) needs the user's say-so.
+const SAFE_URI_SCHEMES: &[&str] = &[
+    "http",
+    "https",
+    "mailto",
+    "steam",
+    "com.epicgames.launcher",
+    "goggalaxy",
+    "uplay",
+    "spotify",
+    "discord",
+    "slack",
+    "msteams",
+    "zoommtg",
+    "vscode",
+    "obsidian",
+    "figma",
+    "notion",
+    "ms-settings",
+];
+
+/// Pause between URLs in a multi-URL step
+const URL_SPACING: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// How long a step may keep working: a launch's cancel flag, and when the
+/// step's timeout runs out.
+pub struct LaunchLimits<'a> {
+    pub cancel: Option<&'a AtomicBool>,
+    pub deadline: Option<Instant>,
+}
+
+impl LaunchLimits<'_> {
+    /// Errs once the launch is cancelled or out of time.
+    fn check(&self) -> Result<(), String> {
+        if self.cancel.is_some_and(|c| c.load(Ordering::SeqCst)) {
+            return Err("Launch cancelled".to_string());
+        }
+        if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err("Step timed out".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Launches a step outside a cancellable launch, bounded by the step timeout.
+/// Like in `run_launch`, the timeout doesn't apply to waiting for a time.
 pub fn launch_step(step: &Step) -> Result<(), String> {
+    let timeout_secs = config::load_config().settings.step_timeout_secs;
+    let timed = timeout_secs > 0 && step.step_type != "wait_until";
+    let limits = LaunchLimits {
+        cancel: None,
+        deadline: timed.then(|| Instant::now() + Duration::from_secs(timeout_secs)),
+    };
+    launch_step_within(step, &limits)
+}
+
+/// Launches a step, giving up on anything it waits for once `limits` says so.
+pub fn launch_step_within(step: &Step, limits: &LaunchLimits) -> Result<(), String> {
     match step.step_type.as_str() {
-        "app" => launch_app(step),
+        "app" => launch_app(step, limits),
         "terminal" => launch_terminal(step),
+        "shell" => run_shell(step, limits),
         "folder" => launch_folder(step),
         "url" => launch_url(step),
+        "http" => send_http(step),
+        "audio" => set_audio_device(step),
+        "wait_until" => {
+            // run_launch waits itself without blocking a thread; this covers
+            // the CLI and anything else launching steps directly
+            let ms = ms_until(step.time.as_deref().unwrap_or(""))?;
+            let until = Instant::now() + Duration::from_millis(ms);
+            while Instant::now() < until {
+                limits.check()?;
+                std::thread::sleep((until - Instant::now()).min(Duration::from_millis(100)));
+            }
+            Ok(())
+        }
         _ => Err(format!("Unknown step type: {}", step.step_type)),
     }
 }
 
-fn launch_app(step: &Step) -> Result<(), String> {
+/// Milliseconds from now until `time` (HH:MM) today, or 0 if it has passed.
+pub fn ms_until(time: &str) -> Result<u64, String> {
+    let target = chrono::NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid time '{}', expected HH:MM", time))?;
+    let now = chrono::Local::now().time();
+    Ok((target - now).num_milliseconds().max(0) as u64)
+}
+
+/// Pairs of step ids that launch the same thing once env vars are expanded.
+/// Each duplicate is paired with the first step of its group.
+pub fn find_duplicate_steps(steps: &[Step]) -> Vec<(String, String)> {
+    let mut first_by_key: HashMap<(String, String, Option<String>), String> = HashMap::new();
+    let mut pairs = Vec::new();
+
+    for step in steps {
+        // Skip the is-it-running lookup; only the resolved target matters here
+        let preview = preview_step(&Step {
+            check_running: Some(false),
+            ..step.clone()
+        });
+        if preview.error.is_some() || preview.resolved.is_empty() {
+            continue;
+        }
+        let key = (
+            step.step_type.clone(),
+            preview.resolved.to_lowercase(),
+            preview.working_dir.map(|d| d.to_lowercase()),
+        );
+        match first_by_key.get(&key) {
+            Some(first) => pairs.push((first.clone(), step.id.clone())),
+            None => {
+                first_by_key.insert(key, step.id.clone());
+            }
+        }
+    }
+
+    pairs
+}
+
+/// Whether an app step's app is up, by window title if the step has one to
+/// match, otherwise by process name.
+fn already_running(step: &Step) -> bool {
+    match step.window_title_match.as_deref().filter(|t| !t.is_empty()) {
+        Some(title) => process::is_window_open(title, &step.process_name),
+        None => !step.process_name.is_empty() && process::is_running(&step.process_name),
+    }
+}
+
+fn launch_app(step: &Step, limits: &LaunchLimits) -> Result<(), String> {
     let target = step.target.as_deref().unwrap_or("");
     if target.is_empty() {
         return Err("No target specified".to_string());
     }
 
     // Check if already running
-    if step.check_running.unwrap_or(true) && !step.process_name.is_empty() {
-        if process::is_running(&step.process_name) {
-            return Ok(()); // Already running, skip
-        }
+    if step.check_running.unwrap_or(true) && already_running(step) {
+        return Ok(()); // Already running, skip
     }
 
     let target = expand_env_vars(target);
 
-    // Check if it's a URI protocol (contains ":" but not ":\")
-    if target.contains(':') && !target.contains(":\\") && !target.starts_with("\\\\") {
+    if let Some(run_as) = &step.run_as_user {
+        return match app_action(&target) {
+            LaunchAction::Uri => Err("A URI can't be opened as another user".to_string()),
+            _ => launch_as_user(run_as, &format!("\"{}\"", target)),
+        };
+    }
+
+    match app_action(&target) {
+        LaunchAction::Uri => {
+            if step.launch_client_first.unwrap_or(false) {
+                start_store_client(&target, limits)?;
+            }
+            launch_uri(&target)
+        }
+        LaunchAction::Exe => launch_exe(&target),
+        _ => launch_via_start(&target),
+    }
+}
+
+/// Starts the store client a game URI goes through and waits for its process.
+/// Schemes not in `STORE_CLIENTS` are left alone. The wait, settling time
+/// included, stays within `limits`, so a cancelled or timed-out launch never
+/// goes on to open the URI.
+fn start_store_client(target: &str, limits: &LaunchLimits) -> Result<(), String> {
+    let Some(&(_, process_name, client_uri)) = STORE_CLIENTS
+        .iter()
+        .find(|(scheme, _, _)| strip_prefix_ignore_case(target, scheme).is_some())
+    else {
+        return Ok(());
+    };
+    if process::is_running(process_name) {
+        return Ok(());
+    }
+
+    launch_uri(client_uri)?;
+    while !process::is_running(process_name) {
+        limits
+            .check()
+            .map_err(|e| format!("{} didn't start: {}", process_name, e))?;
+        std::thread::sleep(Duration::from_millis(500));
+    }
+
+    // The process shows up well before the client is ready for the URI
+    let settled = Instant::now() + CLIENT_SETTLE;
+    let settled = limits.deadline.map_or(settled, |d| d.min(settled));
+    while Instant::now() < settled {
+        limits.check()?;
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    limits.check()
+}
+
+/// Decides how an (env-expanded) app target gets launched.
+fn app_action(target: &str) -> LaunchAction {
+    if is_uri(target) {
         // URI protocol like spotify:, figma:, etc.
-        launch_uri(&target)
-    } else if std::path::Path::new(&target).exists() {
+        LaunchAction::Uri
+    } else if std::path::Path::new(target).exists() {
         // It's a file path
-        launch_exe(&target)
+        LaunchAction::Exe
     } else {
         // Try as a command (e.g. "chrome" which might be in PATH)
-        launch_via_start(&target)
+        LaunchAction::Command
+    }
+}
+
+/// Schemes of the URIs a step would open that are neither built-in safe nor
+/// in `trusted`, lowercased and deduped.
+pub fn untrusted_schemes(step: &Step, trusted: &[String]) -> Vec<String> {
+    let target = step.target.as_deref().unwrap_or("");
+    let uris = match step.step_type.as_str() {
+        "app" => {
+            let target = expand_env_vars(target);
+            if app_action(&target) == LaunchAction::Uri {
+                vec![target]
+            } else {
+                vec![]
+            }
+        }
+        "url" => step_urls(target)
+            .into_iter()
+            .filter(|u| is_uri(u))
+            .collect(),
+        "terminal" | "shell" => {
+            let command = step.command.as_deref().unwrap_or("");
+            command_uri(&expand_env_vars(command)).into_iter().collect()
+        }
+        _ => vec![],
+    };
+
+    let mut schemes: Vec<String> = Vec::new();
+    for uri in uris {
+        let scheme = uri.split(':').next().unwrap_or("").trim().to_lowercase();
+        let known = SAFE_URI_SCHEMES.contains(&scheme.as_str())
+            || trusted.iter().any(|t| t.eq_ignore_ascii_case(&scheme));
+        if !known && !schemes.contains(&scheme) {
+            schemes.push(scheme);
+        }
+    }
+    schemes
+}
+
+/// The URI a command opens, when it is one on its own or is handed to
+/// `start` or `explorer`.
+fn command_uri(command: &str) -> Option<String> {
+    let mut words = command
+        .split_whitespace()
+        .map(|w| w.trim_matches('"'))
+        .filter(|w| !w.is_empty());
+    let first = words.next()?;
+    let opener = ["start", "explorer", "explorer.exe"]
+        .iter()
+        .any(|o| first.eq_ignore_ascii_case(o));
+    if opener {
+        // Past any switches and `start`'s window title
+        words.find(|w| has_uri_scheme(w)).map(str::to_string)
+    } else {
+        has_uri_scheme(first).then(|| first.to_string())
+    }
+}
+
+/// Whether `target` starts with a URI scheme: a letter, then letters,
+/// digits, `+`, `-` or `.`, then ":". Single letters are drive paths.
+fn has_uri_scheme(target: &str) -> bool {
+    let Some((scheme, _)) = target.split_once(':') else {
+        return false;
+    };
+    scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// A URI protocol contains ":" but isn't a drive path (":\") or UNC path.
+pub fn is_uri(target: &str) -> bool {
+    target.contains(':') && !target.contains(":\\") && !target.starts_with("\\\\")
+}
+
+/// Cleans up a pasted target: surrounding quotes (from "Copy as path"),
+/// `file://` URLs, `C:/` style slashes and doubled backslashes. Other URIs
+/// are only trimmed.
+pub fn normalize_target(input: &str, step_type: &str) -> String {
+    let mut target = input.trim();
+    for quote in ['"', '\''] {
+        if target.len() >= 2 && target.starts_with(quote) && target.ends_with(quote) {
+            target = target[1..target.len() - 1].trim();
+        }
+    }
+    if step_type == "url" || step_type == "http" {
+        return target.to_string();
+    }
+
+    let path = if let Some(rest) = strip_prefix_ignore_case(target, "file:") {
+        file_url_to_path(rest)
+    } else {
+        let bytes = target.as_bytes();
+        let drive_slash = bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && bytes[2] == b'/';
+        if !drive_slash && is_uri(target) {
+            return target.to_string();
+        }
+        if drive_slash {
+            target.replace('/', "\\")
+        } else {
+            target.to_string()
+        }
+    };
+
+    collapse_backslashes(&path)
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = s.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &s[prefix.len()..])
+}
+
+/// `///C:/a%20b` -> `C:\a b`, `//server/share` -> `\\server\share`
+fn file_url_to_path(rest: &str) -> String {
+    let path = match rest.strip_prefix("///") {
+        Some(local) => local.to_string(),
+        None => match rest.strip_prefix("//") {
+            Some(unc) => format!("//{}", unc),
+            None => rest.to_string(),
+        },
+    };
+    percent_decode(&path).replace('/', "\\")
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Collapses runs of backslashes, keeping a leading `\\` for UNC paths.
+fn collapse_backslashes(path: &str) -> String {
+    let (prefix, rest) = match path.strip_prefix("\\\\") {
+        Some(rest) => ("\\\\", rest.trim_start_matches('\\')),
+        None => ("", path),
+    };
+    let mut out = String::from(prefix);
+    let mut prev_backslash = false;
+    for c in rest.chars() {
+        if c == '\\' && prev_backslash {
+            continue;
+        }
+        prev_backslash = c == '\\';
+        out.push(c);
     }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchAction {
+    Uri,
+    Exe,
+    Command,
+    Terminal,
+    Shell,
+    Folder,
+    RevealFile,
+    Url,
+    Http,
+    AudioDevice,
+    Wait,
+    Invalid,
+}
+
+/// What `launch_step` would do for a step, without spawning anything.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepPreview {
+    pub step_id: String,
+    pub step_name: String,
+    pub action: LaunchAction,
+    /// Final target, URL or command after env expansion
+    pub resolved: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// True if `check_running` would skip the step right now
+    pub already_running: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Mirrors the dispatch in `launch_step` and reports the result.
+pub fn preview_step(step: &Step) -> StepPreview {
+    let mut preview = StepPreview {
+        step_id: step.id.clone(),
+        step_name: step.name.clone(),
+        action: LaunchAction::Invalid,
+        resolved: String::new(),
+        working_dir: None,
+        already_running: false,
+        error: None,
+    };
+
+    let target = step.target.as_deref().unwrap_or("");
+    match step.step_type.as_str() {
+        "app" => {
+            if target.is_empty() {
+                preview.error = Some("No target specified".to_string());
+                return preview;
+            }
+            preview.already_running = step.check_running.unwrap_or(true) && already_running(step);
+            preview.resolved = expand_env_vars(target);
+            preview.action = app_action(&preview.resolved);
+        }
+        "terminal" => {
+            let command = step.command.as_deref().unwrap_or("");
+            if command.is_empty() {
+                preview.error = Some("No command specified".to_string());
+                return preview;
+            }
+            preview.action = LaunchAction::Terminal;
+            preview.resolved = command.to_string();
+            preview.working_dir = step
+                .working_dir
+                .as_deref()
+                .filter(|d| !d.is_empty())
+                .map(expand_env_vars);
+        }
+        "shell" => {
+            let command = step.command.as_deref().unwrap_or("");
+            if command.is_empty() {
+                preview.error = Some("No command specified".to_string());
+                return preview;
+            }
+            preview.action = LaunchAction::Shell;
+            preview.resolved = expand_env_vars(command);
+            preview.working_dir = step
+                .working_dir
+                .as_deref()
+                .filter(|d| !d.is_empty())
+                .map(expand_env_vars);
+        }
+        "folder" => {
+            if target.is_empty() {
+                preview.error = Some("No folder specified".to_string());
+                return preview;
+            }
+            preview.resolved = expand_env_vars(target);
+            preview.action = if std::path::Path::new(&preview.resolved).is_file() {
+                LaunchAction::RevealFile
+            } else {
+                LaunchAction::Folder
+            };
+        }
+        "url" | "http" => {
+            if target.is_empty() {
+                preview.error = Some("No URL specified".to_string());
+                return preview;
+            }
+            if step.step_type == "http" {
+                preview.action = LaunchAction::Http;
+                preview.resolved = target.to_string();
+            } else {
+                preview.action = LaunchAction::Url;
+                preview.resolved = step_urls(target).join(", ");
+            }
+        }
+        "audio" => {
+            if target.is_empty() {
+                preview.error = Some("No audio device specified".to_string());
+                return preview;
+            }
+            preview.action = LaunchAction::AudioDevice;
+            preview.resolved = target.to_string();
+        }
+        "wait_until" => {
+            let time = step.time.as_deref().unwrap_or("");
+            if let Err(e) = ms_until(time) {
+                preview.error = Some(e);
+                return preview;
+            }
+            preview.action = LaunchAction::Wait;
+            preview.resolved = time.trim().to_string();
+        }
+        _ => preview.error = Some(format!("Unknown step type: {}", step.step_type)),
+    }
+
+    preview
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepHealth {
+    pub step_id: String,
+    pub step_name: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Checks that a step's target still resolves the way `launch_step` would
+/// resolve it, without launching anything.
+pub fn check_step(step: &Step) -> StepHealth {
+    let target = step.target.as_deref().unwrap_or("");
+    let problem = match step.step_type.as_str() {
+        "app" if target.is_empty() => Some("No target specified".to_string()),
+        "app" => {
+            let target = expand_env_vars(target);
+            match app_action(&target) {
+                LaunchAction::Uri if !uri_scheme_registered(&target) => {
+                    Some(format!("No handler registered for {}", target))
+                }
+                LaunchAction::Command if find_on_path(&target).is_none() => {
+                    Some(format!("{} not found", target))
+                }
+                _ => None,
+            }
+        }
+        "terminal" | "shell" if step.command.as_deref().unwrap_or("").is_empty() => {
+            Some("No command specified".to_string())
+        }
+        "terminal" | "shell" => step
+            .working_dir
+            .as_deref()
+            .filter(|d| !d.is_empty() && !std::path::Path::new(&expand_env_vars(d)).is_dir())
+            .map(|d| format!("Working directory {} not found", d)),
+        "folder" if target.is_empty() => Some("No folder specified".to_string()),
+        "folder" if !std::path::Path::new(&expand_env_vars(target)).exists() => {
+            Some(format!("{} not found", target))
+        }
+        "url" | "http" if target.is_empty() => Some("No URL specified".to_string()),
+        "folder" | "url" | "http" => None,
+        "audio" if target.is_empty() => Some("No audio device specified".to_string()),
+        "audio" => match audio::list_audio_devices() {
+            Ok(devices) if devices.iter().any(|d| d.name.eq_ignore_ascii_case(target)) => None,
+            Ok(_) => Some(format!("Audio device '{}' not found", target)),
+            Err(e) => Some(e),
+        },
+        "wait_until" => ms_until(step.time.as_deref().unwrap_or("")).err(),
+        _ => Some(format!("Unknown step type: {}", step.step_type)),
+    };
+
+    StepHealth {
+        step_id: step.id.clone(),
+        step_name: step.name.clone(),
+        ok: problem.is_none(),
+        reason: problem,
+    }
+}
+
+/// Looks a bare command up on PATH, trying PATHEXT extensions like `cmd` does.
+fn find_on_path(command: &str) -> Option<std::path::PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    let exts = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+
+    for dir in std::env::split_paths(&path) {
+        let candidate = dir.join(command);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        for ext in exts.split(';').filter(|e| !e.is_empty()) {
+            let candidate = dir.join(format!("{}{}", command, ext));
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn uri_scheme_registered(uri: &str) -> bool {
+    use winreg::enums::*;
+    use winreg::RegKey;
+
+    let scheme = uri.split(':').next().unwrap_or("");
+    let hkcr = RegKey::predef(HKEY_CLASSES_ROOT);
+    hkcr.open_subkey(scheme)
+        .and_then(|key| key.get_value::<String, _>("URL Protocol"))
+        .is_ok()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn uri_scheme_registered(_uri: &str) -> bool {
+    true
 }
 
 fn launch_uri(uri: &str) -> Result<(), String> {
@@ -86,35 +680,394 @@ fn launch_via_start(target: &str) -> Result<(), String> {
 }
 
 fn launch_terminal(step: &Step) -> Result<(), String> {
+    let invocation = terminal_invocation(step)?;
+    if step.run_as_user.is_some() {
+        return spawn_as_user(&invocation.argv);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(log) = &invocation.log {
+            write_log_wrapper(log, step.command.as_deref().unwrap_or(""))?;
+        }
+        let mut cmd = Command::new(&invocation.argv[0]);
+        cmd.args(&invocation.argv[1..]);
+
+        if !invocation.working_dir.is_empty() {
+            cmd.current_dir(&invocation.working_dir);
+        }
+
+        cmd.creation_flags(CREATE_NO_WINDOW)
+            .spawn()
+            .map_err(|e| format!("Failed to launch terminal: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// What `launch_terminal` runs for a step, shared with the preview so the
+/// two can't drift apart.
+struct TerminalInvocation {
+    /// Program and arguments
+    argv: Vec<String>,
+    /// Where the program starts, when that isn't part of `argv`
+    working_dir: String,
+    /// Output log wrapper that `argv` runs instead of the command
+    log: Option<TerminalLog>,
+}
+
+fn terminal_invocation(step: &Step) -> Result<TerminalInvocation, String> {
     let command = step.command.as_deref().unwrap_or("");
     if command.is_empty() {
         return Err("No command specified".to_string());
     }
 
+    let working_dir = step_working_dir(step)?;
+    // `start` opens a new console whose `cmd` runs the command, then stays
+    // open (/K) or exits (/C)
+    let flag = if step.keep_open.unwrap_or(true) {
+        "/K"
+    } else {
+        "/C"
+    };
+
+    if let Some(run_as) = &step.run_as_user {
+        // runas can't set a working directory, so cd first. No output log:
+        // the other user may not be able to write to ours.
+        let command = if working_dir.is_empty() {
+            command.to_string()
+        } else {
+            format!("cd /d \"{}\" && {}", working_dir, command)
+        };
+        return Ok(TerminalInvocation {
+            argv: runas_argv(run_as, &format!("cmd {} {}", flag, command))?,
+            working_dir: String::new(),
+            log: None,
+        });
+    }
+
+    let log = terminal_log(step);
+    let command = match &log {
+        Some(log) => log.script.to_string_lossy().to_string(),
+        None => command.to_string(),
+    };
+    Ok(TerminalInvocation {
+        argv: ["cmd", "/C", "start", "cmd", flag]
+            .iter()
+            .map(|a| a.to_string())
+            .chain([command])
+            .collect(),
+        working_dir,
+        log,
+    })
+}
+
+/// A terminal or shell step's working directory with env vars expanded.
+fn step_working_dir(step: &Step) -> Result<String, String> {
+    let mut missing = Vec::new();
     let working_dir = step
         .working_dir
         .as_deref()
-        .map(|d| expand_env_vars(d))
+        .map(|d| expand_vars(d, &mut missing))
         .unwrap_or_default();
+    // A scheduled or auto-started launch can run with a thinner environment
+    // than a terminal; refuse rather than start in the wrong directory
+    if !missing.is_empty() && !std::path::Path::new(&working_dir).is_dir() {
+        let message = format!(
+            "Working directory '{}' uses unset variable(s): {}",
+            step.working_dir.as_deref().unwrap_or(""),
+            missing.join(", ")
+        );
+        eprintln!("Warning: {}", message);
+        return Err(message);
+    }
+    Ok(working_dir)
+}
 
-    let keep_open = step.keep_open.unwrap_or(true);
+/// Runs a shell step's command through the system shell with no window and
+/// waits for it. If the launch is cancelled or `limits` runs out of time, the
+/// command is killed along with anything it started. A non-zero exit is an
+/// error carrying what the command wrote to stderr.
+fn run_shell(step: &Step, limits: &LaunchLimits) -> Result<(), String> {
+    let command = step.command.as_deref().unwrap_or("");
+    if command.is_empty() {
+        return Err("No command specified".to_string());
+    }
+    let command = expand_env_vars(command);
+    let working_dir = step_working_dir(step)?;
 
     #[cfg(target_os = "windows")]
-    {
-        let flag = if keep_open { "/K" } else { "/C" };
+    let mut cmd = {
         let mut cmd = Command::new("cmd");
-        cmd.args(["/C", "start", "cmd", flag, command]);
+        // Passed raw: std's quoting escapes quotes with backslashes, which
+        // cmd doesn't understand
+        cmd.arg("/C")
+            .raw_arg(&command)
+            .creation_flags(CREATE_NO_WINDOW);
+        cmd
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", &command]);
+        cmd
+    };
 
-        if !working_dir.is_empty() {
-            cmd.current_dir(&working_dir);
+    if !working_dir.is_empty() {
+        cmd.current_dir(&working_dir);
+    }
+    let mut child = cmd
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run shell command: {}", e))?;
+
+    #[cfg(target_os = "windows")]
+    let tree = job::ProcessTree::new(&child);
+
+    // Drain stderr on its own thread so a chatty command can't fill the pipe
+    let (stderr_tx, stderr_rx) = std::sync::mpsc::channel();
+    if let Some(mut stderr) = child.stderr.take() {
+        std::thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = std::io::Read::read_to_end(&mut stderr, &mut output);
+            let _ = stderr_tx.send(String::from_utf8_lossy(&output).trim().to_string());
+        });
+    }
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(e) => return Err(format!("Failed to wait for shell command: {}", e)),
         }
+        if let Err(e) = limits.check() {
+            // Killing cmd alone would leave whatever it started running
+            #[cfg(target_os = "windows")]
+            if let Some(tree) = &tree {
+                tree.kill();
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("Shell command stopped: {}", e));
+        }
+        std::thread::sleep(SHELL_POLL);
+    };
 
-        cmd.creation_flags(CREATE_NO_WINDOW)
+    if status.success() {
+        return Ok(());
+    }
+    let code = status
+        .code()
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    match stderr_rx.recv_timeout(SHELL_STDERR_GRACE) {
+        Ok(stderr) if !stderr.is_empty() => Err(format!("Exited with code {}: {}", code, stderr)),
+        _ => Err(format!("Exited with code {}", code)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod job {
+    use std::os::windows::io::AsRawHandle;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, TerminateJobObject,
+    };
+
+    /// A job object holding a process, so it can be killed together with
+    /// every process it starts afterwards.
+    pub struct ProcessTree(HANDLE);
+
+    impl ProcessTree {
+        /// None if Windows won't put the process in a job; killing then
+        /// only reaches the process itself.
+        pub fn new(child: &std::process::Child) -> Option<Self> {
+            unsafe {
+                let tree = ProcessTree(CreateJobObjectW(None, PCWSTR::null()).ok()?);
+                AssignProcessToJobObject(tree.0, HANDLE(child.as_raw_handle())).ok()?;
+                Some(tree)
+            }
+        }
+
+        pub fn kill(&self) {
+            let _ = unsafe { TerminateJobObject(self.0, 1) };
+        }
+    }
+
+    impl Drop for ProcessTree {
+        fn drop(&mut self) {
+            let _ = unsafe { CloseHandle(self.0) };
+        }
+    }
+}
+
+/// Runs `program` (a full command line) as another user through `runas`, in a
+/// console of its own where Windows asks for the password. WorkSwitch never
+/// sees it; with `save_credentials`, `/savecred` has Windows remember it.
+fn launch_as_user(run_as: &RunAsUser, program: &str) -> Result<(), String> {
+    spawn_as_user(&runas_argv(run_as, program)?)
+}
+
+/// The `runas` program and arguments that run `program` as `run_as`.
+fn runas_argv(run_as: &RunAsUser, program: &str) -> Result<Vec<String>, String> {
+    let username = run_as.username.trim();
+    if username.is_empty() || username.contains('"') {
+        return Err(format!("Invalid user name '{}'", run_as.username));
+    }
+
+    let mut argv = vec!["runas".to_string(), format!("/user:{}", username)];
+    // Only when asked for: it stores the password in Credential Manager
+    if run_as.save_credentials {
+        argv.push("/savecred".to_string());
+    }
+    // runas takes the whole program command line as one argument
+    argv.push(program.to_string());
+    Ok(argv)
+}
+
+/// Starts a `runas_argv` command line in a console of its own, where runas
+/// asks for the password.
+fn spawn_as_user(argv: &[String]) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NEW_CONSOLE: u32 = 0x00000010;
+
+        Command::new(&argv[0])
+            .args(&argv[1..])
+            .creation_flags(CREATE_NEW_CONSOLE)
             .spawn()
-            .map_err(|e| format!("Failed to launch terminal: {}", e))?;
+            .map_err(|e| {
+                let user = argv[1].trim_start_matches("/user:");
+                format!("Failed to run as {}: {}", user, e)
+            })?;
+        Ok(())
     }
 
-    Ok(())
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = argv;
+        Err("Running a step as another user is only supported on Windows".to_string())
+    }
+}
+
+/// The exact command line `launch_terminal` would pass to CreateProcess for
+/// this step, and the folder it starts in. `%VAR%`s in the command are left
+/// for cmd to expand.
+pub fn preview_terminal_command(step: &Step) -> Result<String, String> {
+    let invocation = terminal_invocation(step)?;
+    let argv: Vec<&str> = invocation.argv.iter().map(String::as_str).collect();
+    let line = windows_command_line(&argv);
+    if invocation.working_dir.is_empty() {
+        Ok(line)
+    } else {
+        Ok(format!("{}\nin {}", line, invocation.working_dir))
+    }
+}
+
+pub struct TerminalLog {
+    /// Batch file that runs the step's command and tees its output
+    pub script: PathBuf,
+    pub log: PathBuf,
+}
+
+/// With `log_terminal_output` on, a terminal that closes when its command
+/// finishes runs through a wrapper that keeps the output in logs/<step>.log.
+pub fn terminal_log(step: &Step) -> Option<TerminalLog> {
+    if step.step_type != "terminal" || step.keep_open.unwrap_or(true) {
+        return None;
+    }
+    if !config::load_config().settings.log_terminal_output {
+        return None;
+    }
+    let dir = config::config_path().with_file_name("logs");
+    Some(TerminalLog {
+        script: dir.join(format!("{}.cmd", step.id)),
+        log: dir.join(format!("{}.log", step.id)),
+    })
+}
+
+/// A script rather than a redirect in the command line, because Rust's
+/// argument quoting escapes quotes in a way cmd doesn't understand.
+#[cfg(target_os = "windows")]
+fn write_log_wrapper(log: &TerminalLog, command: &str) -> Result<(), String> {
+    if let Some(dir) = log.script.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create log folder: {}", e))?;
+    }
+    let log_path = log.log.to_string_lossy().replace('\'', "''");
+    let script = format!(
+        "@echo off\r\n({}) 2>&1 | powershell -NoProfile -Command \"$input | Tee-Object -FilePath '{}'\"\r\n",
+        escape_batch_percents(command),
+        escape_batch_percents(&log_path)
+    );
+    std::fs::write(&log.script, script).map_err(|e| format!("Failed to write log wrapper: {}", e))
+}
+
+/// Makes a command mean the same in a batch file as at the prompt. `%NAME%`
+/// for a set variable expands either way and is kept; any other `%` is
+/// literal at the prompt but eaten in a script, so it's doubled.
+#[cfg(any(target_os = "windows", test))]
+fn escape_batch_percents(command: &str) -> String {
+    let mut result = String::with_capacity(command.len());
+    let mut rest = command;
+    while let Some(pos) = rest.find('%') {
+        result.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let var = after
+            .find('%')
+            .map(|end| &after[..end])
+            .filter(|name| is_var_name(name) && std::env::var_os(name).is_some());
+        match var {
+            Some(name) => {
+                result.push('%');
+                result.push_str(name);
+                result.push('%');
+                rest = &after[name.len() + 1..];
+            }
+            None => {
+                result.push_str("%%");
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Joins arguments the way Rust's `Command` does on Windows: the program is
+/// always quoted, other arguments only when empty or containing whitespace,
+/// with quotes (and the backslashes before them) escaped.
+fn windows_command_line(argv: &[&str]) -> String {
+    let mut line = String::new();
+    for (i, arg) in argv.iter().enumerate() {
+        if i > 0 {
+            line.push(' ');
+        }
+        let quote = i == 0 || arg.is_empty() || arg.contains([' ', '\t']);
+        if quote {
+            line.push('"');
+        }
+        let mut backslashes = 0;
+        for c in arg.chars() {
+            if c == '\\' {
+                backslashes += 1;
+            } else {
+                if c == '"' {
+                    line.extend(std::iter::repeat_n('\\', backslashes + 1));
+                }
+                backslashes = 0;
+            }
+            line.push(c);
+        }
+        if quote {
+            line.extend(std::iter::repeat_n('\\', backslashes));
+            line.push('"');
+        }
+    }
+    line
 }
 
 fn launch_folder(step: &Step) -> Result<(), String> {
@@ -125,6 +1078,11 @@ fn launch_folder(step: &Step) -> Result<(), String> {
 
     let target = expand_env_vars(target);
 
+    // A file target opens its containing folder with the file selected
+    if std::path::Path::new(&target).is_file() {
+        return reveal_file(&target);
+    }
+
     #[cfg(target_os = "windows")]
     {
         Command::new("explorer")
@@ -137,39 +1095,358 @@ fn launch_folder(step: &Step) -> Result<(), String> {
     Ok(())
 }
 
+fn reveal_file(path: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        // explorer wants the quotes after the comma, so bypass std's arg quoting
+        Command::new("explorer")
+            .raw_arg(format!("/select,\"{}\"", path))
+            .creation_flags(DETACHED_PROCESS)
+            .spawn()
+            .map_err(|e| format!("Failed to reveal {}: {}", path, e))?;
+    }
+    Ok(())
+}
+
 fn launch_url(step: &Step) -> Result<(), String> {
-    let target = step.target.as_deref().unwrap_or("");
-    if target.is_empty() {
+    let urls = step_urls(step.target.as_deref().unwrap_or(""));
+    if urls.is_empty() {
+        return Err("No URL specified".to_string());
+    }
+    if urls.len() > 1 {
+        if let Some(bad) = urls.iter().find(|url| !is_uri(url)) {
+            return Err(format!("'{}' is not a URL", bad));
+        }
+    }
+
+    for (i, url) in urls.iter().enumerate() {
+        if i > 0 {
+            // Give the browser a moment so the tabs land in one window
+            std::thread::sleep(URL_SPACING);
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            Command::new("cmd")
+                .args(["/C", "start", "", url])
+                .creation_flags(CREATE_NO_WINDOW)
+                .spawn()
+                .map_err(|e| format!("Failed to open URL {}: {}", url, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits a url step's target into its URLs, env-expanded. Entries go one
+/// per line; a line is also split on commas, but only if every piece is a
+/// URL, so a comma inside a query string doesn't break the link.
+fn step_urls(target: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    for line in target.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let pieces: Vec<&str> = line.split(',').map(str::trim).collect();
+        if pieces.len() > 1 && pieces.iter().all(|p| is_uri(p)) {
+            urls.extend(pieces.iter().map(|p| expand_env_vars(p)));
+        } else {
+            urls.push(expand_env_vars(line));
+        }
+    }
+    urls
+}
+
+fn set_audio_device(step: &Step) -> Result<(), String> {
+    let name = step.target.as_deref().unwrap_or("");
+    if name.is_empty() {
+        return Err("No audio device specified".to_string());
+    }
+    audio::set_default_device(name)
+}
+
+/// Sends the step's HTTP request, with env vars expanded in the URL, headers
+/// and body, failing on a non-2xx response.
+fn send_http(step: &Step) -> Result<(), String> {
+    let url = step.target.as_deref().unwrap_or("");
+    if url.is_empty() {
         return Err("No URL specified".to_string());
     }
+    let url = expand_env_vars(url);
 
-    #[cfg(target_os = "windows")]
-    {
-        Command::new("cmd")
-            .args(["/C", "start", "", target])
-            .creation_flags(CREATE_NO_WINDOW)
-            .spawn()
-            .map_err(|e| format!("Failed to open URL {}: {}", target, e))?;
+    let method = step.method.as_deref().unwrap_or("POST").to_uppercase();
+    let method = reqwest::Method::from_bytes(method.as_bytes())
+        .map_err(|_| format!("Invalid HTTP method: {}", method))?;
+
+    // Match the launch timeout so the request doesn't outlive the step
+    let timeout_secs = config::load_config().settings.step_timeout_secs;
+    let client = reqwest::blocking::Client::builder()
+        .timeout((timeout_secs > 0).then(|| std::time::Duration::from_secs(timeout_secs)))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut request = client.request(method, &url);
+    for (name, value) in step.headers.iter().flatten() {
+        request = request.header(name, expand_env_vars(value));
+    }
+    if let Some(body) = &step.body {
+        request = request.body(expand_env_vars(body));
+    }
+
+    let response = request
+        .send()
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
     }
 
     Ok(())
 }
 
+/// Expands `%VAR%`, `${VAR}` and `$VAR` on every platform so profiles stay
+/// portable. `${VAR:-default}` uses the default if VAR is unset or empty;
+/// other unknown variables are left as written.
 fn expand_env_vars(input: &str) -> String {
-    let mut result = input.to_string();
-    // Expand %VAR% patterns
-    while let Some(start) = result.find('%') {
-        if let Some(end) = result[start + 1..].find('%') {
-            let var_name = &result[start + 1..start + 1 + end];
-            if let Ok(value) = std::env::var(var_name) {
-                result = format!("{}{}{}", &result[..start], value, &result[start + 2 + end..]);
-            } else {
-                // Can't expand, skip this one
-                break;
+    expand_vars(input, &mut Vec::new())
+}
+
+/// `expand_env_vars`, also collecting the names of variables that weren't set.
+fn expand_vars(input: &str, missing: &mut Vec<String>) -> String {
+    #[cfg(not(target_os = "windows"))]
+    let input: &str = &match (input.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => format!("{}/{}", home, rest),
+        _ => input.to_string(),
+    };
+
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find(['%', '$']) {
+        result.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        // Work out the variable name and how much of the input it spans
+        let mut default = None;
+        let percent = rest[pos..].starts_with('%');
+        let (name, consumed) = if percent {
+            match after.find('%') {
+                Some(end) => (&after[..end], end + 2),
+                None => ("", 1),
+            }
+        } else if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => {
+                    // `${VAR:-default}` falls back when VAR is unset or empty
+                    let inner = &braced[..end];
+                    let name = match inner.split_once(":-") {
+                        Some((name, fallback)) => {
+                            default = Some(fallback);
+                            name
+                        }
+                        None => inner,
+                    };
+                    (name, end + 3)
+                }
+                None => ("", 1),
             }
         } else {
-            break;
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end + 1)
+        };
+
+        let value = lookup_var(name);
+        match (value, default) {
+            (Some(value), Some(default)) if value.is_empty() => result.push_str(default),
+            (Some(value), _) => result.push_str(&value),
+            (None, Some(default)) => result.push_str(default),
+            (None, None) if percent => {
+                // The `%` may be a literal one (`50%off`), so only it is
+                // kept; the next `%` can still open a real variable
+                if is_var_name(name) {
+                    missing.push(name.to_string());
+                }
+                result.push('%');
+                rest = &rest[pos + 1..];
+                continue;
+            }
+            (None, None) => {
+                if !name.is_empty() {
+                    missing.push(name.to_string());
+                }
+                result.push_str(&rest[pos..pos + consumed]);
+            }
         }
+        rest = &rest[pos + consumed..];
     }
+    result.push_str(rest);
     result
 }
+
+/// Whether the text between two `%` could be a variable name rather than
+/// prose around literal percent signs.
+fn is_var_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || c == '=')
+}
+
+fn lookup_var(name: &str) -> Option<String> {
+    if name.is_empty() {
+        return None;
+    }
+    std::env::var(name)
+        .ok()
+        .or_else(|| registry_env_var(name))
+        .or_else(|| {
+            // Profiles made on Windows commonly use %USERPROFILE%
+            if name.eq_ignore_ascii_case("USERPROFILE") {
+                home_dir()
+            } else {
+                None
+            }
+        })
+}
+
+/// Reads a variable from the user's then the machine's saved environment.
+/// WorkSwitch's own environment is fixed when it starts (often at login via
+/// the Run key), so variables set since then are only found here.
+#[cfg(target_os = "windows")]
+fn registry_env_var(name: &str) -> Option<String> {
+    use winreg::enums::*;
+    use winreg::RegKey;
+
+    let user = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Environment");
+    let machine = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(r"SYSTEM\CurrentControlSet\Control\Session Manager\Environment");
+    let value = [user, machine]
+        .into_iter()
+        .flatten()
+        .find_map(|key| key.get_value::<String, _>(name).ok())?;
+
+    // REG_EXPAND_SZ values come back unexpanded; don't recurse into a self-reference
+    let self_reference = format!("%{}%", name).to_lowercase();
+    if value.to_lowercase().contains(&self_reference) {
+        Some(value)
+    } else {
+        Some(expand_env_vars(&value))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn registry_env_var(_name: &str) -> Option<String> {
+    None
+}
+
+fn home_dir() -> Option<String> {
+    std::env::var("USERPROFILE")
+        .or_else(|_| std::env::var("HOME"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_percent_is_kept() {
+        assert_eq!(expand_env_vars("50%discount"), "50%discount");
+        assert_eq!(expand_env_vars("50%discount 100%"), "50%discount 100%");
+    }
+
+    #[test]
+    fn undefined_percent_var_is_left_and_reported() {
+        std::env::remove_var("WORKSWITCH_TEST_UNSET");
+        let mut missing = Vec::new();
+        let out = expand_vars(r"%WORKSWITCH_TEST_UNSET%\app", &mut missing);
+        assert_eq!(out, r"%WORKSWITCH_TEST_UNSET%\app");
+        assert_eq!(missing, ["WORKSWITCH_TEST_UNSET"]);
+    }
+
+    #[test]
+    fn literal_percent_next_to_variables() {
+        std::env::set_var("WORKSWITCH_TEST_MIXED", "dir");
+        let mut missing = Vec::new();
+        let out = expand_vars("50%discount %WORKSWITCH_TEST_MIXED%", &mut missing);
+        assert_eq!(out, "50%discount dir");
+        let out = expand_vars("%WORKSWITCH_TEST_MIXED% is 100% done", &mut missing);
+        assert_eq!(out, "dir is 100% done");
+        let out = expand_vars("100% done %WORKSWITCH_TEST_MIXED%", &mut missing);
+        assert_eq!(out, "100% done dir");
+        assert!(missing.is_empty(), "unexpected missing: {:?}", missing);
+    }
+
+    #[test]
+    fn braced_default_ignored_when_set() {
+        std::env::set_var("WORKSWITCH_TEST_SET", "real");
+        assert_eq!(expand_env_vars("${WORKSWITCH_TEST_SET:-fallback}"), "real");
+    }
+
+    #[test]
+    fn braced_default_used_when_unset() {
+        std::env::remove_var("WORKSWITCH_TEST_DEFAULTED");
+        let mut missing = Vec::new();
+        let out = expand_vars("${WORKSWITCH_TEST_DEFAULTED:-C:\\Tools}\\bin", &mut missing);
+        assert_eq!(out, "C:\\Tools\\bin");
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn braced_unset_without_default_is_left_and_reported() {
+        std::env::remove_var("WORKSWITCH_TEST_NO_DEFAULT");
+        let mut missing = Vec::new();
+        let out = expand_vars("${WORKSWITCH_TEST_NO_DEFAULT}/bin", &mut missing);
+        assert_eq!(out, "${WORKSWITCH_TEST_NO_DEFAULT}/bin");
+        assert_eq!(missing, ["WORKSWITCH_TEST_NO_DEFAULT"]);
+    }
+
+    #[test]
+    fn batch_percents_are_doubled_unless_a_set_variable() {
+        std::env::set_var("WORKSWITCH_TEST_BATCH", "x");
+        std::env::remove_var("WORKSWITCH_TEST_BATCH_UNSET");
+        assert_eq!(
+            escape_batch_percents("echo 100% %WORKSWITCH_TEST_BATCH%"),
+            "echo 100%% %WORKSWITCH_TEST_BATCH%"
+        );
+        assert_eq!(
+            escape_batch_percents("echo %WORKSWITCH_TEST_BATCH_UNSET% %1"),
+            "echo %%WORKSWITCH_TEST_BATCH_UNSET%% %%1"
+        );
+    }
+
+    fn terminal_step(working_dir: &str) -> Step {
+        Step {
+            step_type: "terminal".to_string(),
+            command: Some("echo hi".to_string()),
+            working_dir: Some(working_dir.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn terminal_working_dir_is_expanded() {
+        let temp = std::env::temp_dir();
+        std::env::set_var("WORKSWITCH_TEST_WORKDIR", &temp);
+        let invocation = terminal_invocation(&terminal_step("%WORKSWITCH_TEST_WORKDIR%")).unwrap();
+        assert_eq!(invocation.working_dir, temp.to_string_lossy());
+    }
+
+    #[test]
+    fn terminal_working_dir_with_unset_variable_is_refused() {
+        std::env::remove_var("WORKSWITCH_TEST_WORKDIR_UNSET");
+        let err = terminal_invocation(&terminal_step("${WORKSWITCH_TEST_WORKDIR_UNSET}/src"))
+            .err()
+            .unwrap();
+        assert!(err.contains("WORKSWITCH_TEST_WORKDIR_UNSET"), "{}", err);
+    }
+
+    fn shell_step(command: &str) -> Step {
+        Step {
+            step_type: "shell".to_string(),
+            command: Some(command.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn shell_command_opening_a_uri_is_untrusted() {
+        let step = shell_step("start foo:bar");
+        assert_eq!(untrusted_schemes(&step, &[]), ["foo"]);
+        assert!(untrusted_schemes(&step, &["FOO".to_string()]).is_empty());
+    }
+}
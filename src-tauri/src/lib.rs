@@ -1,60 +1,184 @@
+mod audio;
+mod cli;
+#[cfg(target_os = "windows")]
+mod com;
 mod commands;
+mod conditions;
 mod config;
+mod desktop;
 mod discovery;
+mod error;
+mod housekeeping;
+mod jumplist;
 mod launcher;
+mod lifecycle;
+mod monitor;
 mod process;
 mod scheduler;
+mod search;
+mod session;
+mod system;
 mod tray;
+mod unlock;
 
-use commands::LaunchState;
+use commands::{ActiveProfile, LastLaunch, LaunchState, SchedulerState};
 use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let flags = cli::StartupFlags::from_args(std::env::args().skip(1));
+    if flags.list_profiles {
+        cli::attach_parent_console();
+        cli::list_profiles(flags.json);
+        std::process::exit(0);
+    }
+    if let Some(name) = &flags.launch {
+        cli::attach_parent_console();
+        std::process::exit(cli::launch_profile_headless(name));
+    }
+
     tauri::Builder::default()
+        // Registered first so a second copy hands over its arguments and exits
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            let flags = cli::StartupFlags::from_args(args.into_iter().skip(1));
+            match flags.launch_id {
+                Some(id) => launch_forwarded(app.clone(), id),
+                None => {
+                    let _ = commands::show_window(app.clone());
+                }
+            }
+        }))
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .manage(LaunchState::default())
+        .manage(LastLaunch::load())
+        .manage(ActiveProfile::load())
+        .manage(SchedulerState::default())
+        .manage(monitor::WatchRegistry::default())
         .invoke_handler(tauri::generate_handler![
             commands::get_config,
             commands::save_config,
+            commands::reset_settings,
             commands::launch_profile,
+            commands::launch_profile_by_id,
+            commands::find_untrusted_uris,
+            commands::restart_launch,
+            commands::estimate_launch_time,
             commands::cancel_launch,
+            commands::resolve_failure_prompt,
+            commands::preview_profile,
+            commands::preview_terminal_command,
+            commands::check_profile_health,
+            commands::find_duplicate_steps,
+            commands::normalize_target,
+            commands::set_steps_enabled,
+            commands::toggle_all_steps,
             commands::is_process_running,
             commands::kill_process,
+            commands::kill_processes,
+            commands::close_all_launched,
+            commands::set_last_launch_processes,
+            commands::close_profile,
+            commands::stop_step,
+            commands::get_active_profile,
+            commands::get_profile_stats,
+            commands::set_scheduler_paused,
+            commands::set_schedule_enabled,
+            commands::get_scheduler_paused,
+            commands::cleanup_temp_artifacts,
+            commands::quick_search,
+            commands::find_profiles_using,
             commands::get_running_processes_for_steps,
+            commands::get_process_stats,
+            commands::get_open_windows,
+            commands::get_current_ssid,
+            commands::get_power_status,
+            commands::get_system_info,
+            commands::get_free_space,
+            commands::list_audio_devices,
             commands::browse_file,
             commands::browse_folder,
             commands::scan_apps,
+            commands::scan_apps_grouped,
             commands::show_window,
             commands::set_auto_start,
+            commands::get_auto_start,
             commands::browse_save_profile,
             commands::browse_import_profile,
             commands::export_profile,
             commands::import_profile,
+            commands::import_profile_from_url,
             commands::save_profile_file,
             commands::load_profile_file,
+            commands::import_profiles_from_dir,
+            commands::capture_running_as_profile,
         ])
-        .setup(|app| {
+        .setup(move |app| {
             // Create tray icon
-            if let Err(e) = tray::create_tray(app.handle()) {
-                eprintln!("Failed to create tray: {}", e);
-            }
+            let has_tray = match tray::create_tray(app.handle()) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("Failed to create tray: {}", e);
+                    app.dialog()
+                        .message(
+                            "WorkSwitch couldn't add its icon to the system tray. \
+                             Closing or minimizing the window won't send it to the tray \
+                             this session.",
+                        )
+                        .title("No system tray")
+                        .kind(MessageDialogKind::Warning)
+                        .show(|_| {});
+                    false
+                }
+            };
 
-            // Check start_minimized setting
+            // The window starts hidden so staying in the tray doesn't flash it,
+            // unless there's no tray to bring it back from
             let cfg = config::load_config();
-            if cfg.settings.start_minimized {
+            if !has_tray || (!cfg.settings.start_minimized && !flags.minimized) {
                 if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.hide();
+                    let _ = window.show();
                 }
             }
 
+            // Taskbar jump list with a task per profile
+            let jump_cfg = cfg.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = jumplist::rebuild_jump_list(&jump_cfg) {
+                    eprintln!("{}", e);
+                }
+            });
+
+            // Clear out temp files an earlier run left behind
+            let cleanup_cfg = cfg.clone();
+            std::thread::spawn(move || {
+                housekeeping::cleanup_temp_artifacts(&cleanup_cfg);
+            });
+
             // Start schedule checker
             let app_handle = app.handle().clone();
             std::thread::spawn(move || {
                 scheduler::run_scheduler(app_handle);
             });
 
+            // Watch launched steps for crashes
+            let app_handle = app.handle().clone();
+            std::thread::spawn(move || {
+                monitor::run_monitor(app_handle);
+            });
+
+            // Listen for session unlock to fire on-unlock schedules
+            let app_handle = app.handle().clone();
+            std::thread::spawn(move || {
+                unlock::run_unlock_listener(app_handle);
+            });
+
+            // Started from the jump list with no instance running yet
+            if let Some(id) = flags.launch_id.clone() {
+                launch_forwarded(app.handle().clone(), id);
+            }
+
             // Launch startup apps
             if !cfg.startup_apps.is_empty() {
                 let startup_apps = cfg.startup_apps.clone();
@@ -75,12 +199,29 @@ pub fn run() {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 // Check minimize_to_tray setting
                 let cfg = config::load_config();
-                if cfg.settings.minimize_to_tray {
+                if cfg.settings.minimize_to_tray && tray::tray_available(window.app_handle()) {
                     api.prevent_close();
                     let _ = window.hide();
+                } else if lifecycle::close_apps_on_exit(window.app_handle()) {
+                    api.prevent_close();
                 }
             }
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+/// Launches a profile asked for on the command line (a jump list task) in this
+/// instance, so it goes through the same guard and hooks as the window.
+fn launch_forwarded(app: tauri::AppHandle, profile_id: String) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = commands::launch_profile_by_id(profile_id, app.clone()).await {
+            eprintln!("Jump list launch failed: {}", e);
+            app.dialog()
+                .message(e.to_string())
+                .title("Couldn't launch profile")
+                .kind(MessageDialogKind::Error)
+                .show(|_| {});
+        }
+    });
+}
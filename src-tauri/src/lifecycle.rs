@@ -0,0 +1,138 @@
+use crate::commands::LastLaunch;
+use crate::config::{self, Settings};
+use crate::process;
+use serde::Serialize;
+use std::sync::mpsc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+/// How long to wait for an answer when the window is hidden in the tray
+const HIDDEN_CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CloseReport {
+    pub closed: Vec<String>,
+    pub failed: Vec<CloseFailure>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CloseFailure {
+    pub name: String,
+    pub error: String,
+}
+
+/// Gracefully closes each process that is still running. Names that aren't
+/// running count as closed.
+pub fn close_processes(names: &[String]) -> CloseReport {
+    let mut report = CloseReport::default();
+
+    for name in names {
+        if name.is_empty() {
+            continue;
+        }
+        if !process::is_running(name) {
+            report.closed.push(name.clone());
+            continue;
+        }
+        match process::close_process(name) {
+            Ok(()) => report.closed.push(name.clone()),
+            Err(e) => report.failed.push(CloseFailure {
+                name: name.clone(),
+                error: e,
+            }),
+        }
+    }
+
+    report
+}
+
+/// Closes the apps WorkSwitch launched before quitting, if `close_on_exit` is
+/// enabled. Returns true when it has taken over the exit: the caller must not
+/// exit itself, the app exits once the apps are dealt with.
+pub fn close_apps_on_exit(app: &AppHandle) -> bool {
+    let cfg = config::load_config();
+    let names = processes_to_close(&cfg.settings, app.state::<LastLaunch>().get_processes());
+    if names.is_empty() {
+        return false;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let running: Vec<String> = names
+            .into_iter()
+            .filter(|name| process::is_running(name))
+            .collect();
+
+        // "Leave running" still quits WorkSwitch, it just skips the kills
+        if !running.is_empty()
+            && (!cfg.settings.confirm_close_on_exit || confirm_close(&app, &running))
+        {
+            close_processes(&running);
+        }
+        app.exit(0);
+    });
+
+    true
+}
+
+/// The tracked processes that quitting should close: none unless
+/// `close_on_exit` is on.
+fn processes_to_close(settings: &Settings, tracked: Vec<String>) -> Vec<String> {
+    if settings.close_on_exit {
+        tracked
+    } else {
+        Vec::new()
+    }
+}
+
+/// Asks whether the still-running apps should be closed. Anything but an
+/// explicit "Close apps" leaves them running, and if the window is hidden
+/// nobody may be around to answer, so that case gives up after a timeout.
+fn confirm_close(app: &AppHandle, running: &[String]) -> bool {
+    let window_hidden = app
+        .get_webview_window("main")
+        .and_then(|w| w.is_visible().ok())
+        .map(|visible| !visible)
+        .unwrap_or(true);
+
+    let (tx, rx) = mpsc::channel();
+    app.dialog()
+        .message(format!(
+            "These apps launched by WorkSwitch are still running:\n\n{}\n\nClose them before quitting?",
+            running.join("\n")
+        ))
+        .title("Close launched apps?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Close apps".to_string(),
+            "Leave running".to_string(),
+        ))
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+
+    if window_hidden {
+        rx.recv_timeout(HIDDEN_CONFIRM_TIMEOUT).unwrap_or(false)
+    } else {
+        rx.recv().unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    #[test]
+    fn close_on_exit_decides_whether_tracked_processes_are_closed() {
+        let tracked = vec!["code.exe".to_string(), "slack.exe".to_string()];
+        let mut settings = AppConfig::default().settings;
+
+        settings.close_on_exit = false;
+        assert!(processes_to_close(&settings, tracked.clone()).is_empty());
+
+        settings.close_on_exit = true;
+        assert_eq!(processes_to_close(&settings, tracked.clone()), tracked);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::config::Step;
+use crate::launcher;
+use crate::process;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Give up on a watched step whose process never showed up
+const STARTUP_GRACE: Duration = Duration::from_secs(120);
+const MAX_RESTARTS: u32 = 3;
+const BASE_BACKOFF: Duration = Duration::from_secs(5);
+
+struct WatchedStep {
+    step: Step,
+    watched_since: Instant,
+    seen_running: bool,
+    restarts: u32,
+    restart_at: Option<Instant>,
+}
+
+/// Launched steps being watched for crashes, keyed by lowercase process name.
+#[derive(Default)]
+pub struct WatchRegistry {
+    steps: Mutex<HashMap<String, WatchedStep>>,
+}
+
+impl WatchRegistry {
+    /// Starts watching a step if it opted in and has a process name.
+    pub fn watch(&self, step: &Step) {
+        let wanted = step.watch.unwrap_or(false) || step.restart_on_crash.unwrap_or(false);
+        if !wanted || step.process_name.is_empty() {
+            return;
+        }
+        if let Ok(mut steps) = self.steps.lock() {
+            steps.insert(
+                step.process_name.to_lowercase(),
+                WatchedStep {
+                    step: step.clone(),
+                    watched_since: Instant::now(),
+                    seen_running: false,
+                    restarts: 0,
+                    restart_at: None,
+                },
+            );
+        }
+    }
+
+    pub fn unwatch(&self, process_name: &str) {
+        if let Ok(mut steps) = self.steps.lock() {
+            steps.remove(&process_name.to_lowercase());
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut steps) = self.steps.lock() {
+            steps.clear();
+        }
+    }
+}
+
+enum Action {
+    Restart(Step, u32),
+    Exited(Step),
+}
+
+/// Polls watched processes, restarting crashed ones with a backoff. Runs
+/// forever on its own thread.
+pub fn run_monitor(app: tauri::AppHandle) {
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        let registry = app.state::<WatchRegistry>();
+        let mut actions = Vec::new();
+        {
+            let Ok(mut steps) = registry.steps.lock() else {
+                continue;
+            };
+            if steps.is_empty() {
+                continue;
+            }
+
+            let running = process::get_running_processes();
+            let now = Instant::now();
+            steps.retain(|name, watched| {
+                if running.contains(name) {
+                    watched.seen_running = true;
+                    watched.restart_at = None;
+                    return true;
+                }
+                if !watched.seen_running {
+                    // Still starting up, or it never launched at all
+                    return now.duration_since(watched.watched_since) < STARTUP_GRACE;
+                }
+
+                let can_restart = watched.step.restart_on_crash.unwrap_or(false)
+                    && watched.restarts < MAX_RESTARTS;
+                if !can_restart {
+                    actions.push(Action::Exited(watched.step.clone()));
+                    return false;
+                }
+
+                match watched.restart_at {
+                    None => {
+                        watched.restart_at = Some(now + BASE_BACKOFF * 2u32.pow(watched.restarts));
+                    }
+                    Some(at) if now >= at => {
+                        watched.restarts += 1;
+                        watched.seen_running = false;
+                        watched.watched_since = now;
+                        watched.restart_at = None;
+                        actions.push(Action::Restart(watched.step.clone(), watched.restarts));
+                    }
+                    Some(_) => {}
+                }
+                true
+            });
+        }
+
+        for action in actions {
+            match action {
+                Action::Restart(step, restarts) => {
+                    if let Err(e) = launcher::launch_step(&step) {
+                        eprintln!("Failed to restart '{}': {}", step.name, e);
+                    }
+                    let _ = app.emit(
+                        "step-restarted",
+                        serde_json::json!({
+                            "step_name": step.name,
+                            "process_name": step.process_name,
+                            "restarts": restarts
+                        }),
+                    );
+                }
+                Action::Exited(step) => {
+                    let _ = app.emit(
+                        "step-exited",
+                        serde_json::json!({
+                            "step_name": step.name,
+                            "process_name": step.process_name
+                        }),
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::collections::HashSet;
 use std::process::Command;
 
@@ -7,51 +8,159 @@ use std::os::windows::process::CommandExt;
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+/// Lowercased exe names of every running process.
 pub fn get_running_processes() -> HashSet<String> {
-    let mut set = HashSet::new();
+    #[cfg(target_os = "windows")]
+    {
+        win32::process_names().into_values().collect()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        HashSet::new()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowInfo {
+    pub title: String,
+    pub process_name: String,
+    pub pid: u32,
+}
+
+/// Lists the visible, titled top-level windows on the desktop, every window
+/// of a process included.
+pub fn get_open_windows() -> Vec<WindowInfo> {
+    let mut windows = Vec::new();
 
     #[cfg(target_os = "windows")]
     {
-        let output = Command::new("tasklist")
-            .args(["/FO", "CSV", "/NH"])
-            .creation_flags(CREATE_NO_WINDOW)
-            .output();
+        let names = win32::process_names();
+        for (pid, title) in win32::top_level_windows() {
+            windows.push(WindowInfo {
+                title,
+                process_name: names.get(&pid).cloned().unwrap_or_default(),
+                pid,
+            });
+        }
+    }
 
-        if let Ok(output) = output {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                // Format: "process.exe","PID","Session Name","Session#","Mem Usage"
-                if let Some(name) = line.split(',').next() {
-                    let name = name.trim_matches('"').to_lowercase();
-                    if !name.is_empty() {
-                        set.insert(name);
-                    }
-                }
-            }
+    windows
+}
+
+/// True if a window title contains `pattern`, case-insensitively. A non-empty
+/// `process_name` narrows the match to that process's windows. Cheap enough
+/// to poll: nothing is spawned, and processes are only looked up once a
+/// title matches.
+pub fn is_window_open(pattern: &str, process_name: &str) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        let pattern = pattern.to_lowercase();
+        let pids: Vec<u32> = win32::top_level_windows()
+            .into_iter()
+            .filter(|(_, title)| title.to_lowercase().contains(&pattern))
+            .map(|(pid, _)| pid)
+            .collect();
+        if pids.is_empty() || process_name.is_empty() {
+            return !pids.is_empty();
         }
+        let names = win32::process_names();
+        pids.iter().any(|pid| {
+            names
+                .get(pid)
+                .is_some_and(|n| n.eq_ignore_ascii_case(process_name))
+        })
     }
 
-    set
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (pattern, process_name);
+        false
+    }
 }
 
-pub fn is_running(name: &str) -> bool {
-    // Use targeted tasklist filter instead of listing all processes
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessStat {
+    pub name: String,
+    pub pid: u32,
+    /// Share of total CPU capacity, 0-100
+    pub cpu_percent: f32,
+    /// Working set
+    pub memory_bytes: u64,
+}
+
+/// How long CPU time is measured over for `get_process_stats`
+#[cfg(target_os = "windows")]
+const CPU_SAMPLE: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Returns one entry per running instance of each of the given process names.
+/// CPU use is measured over a short sample, so this blocks for `CPU_SAMPLE`.
+pub fn get_process_stats(names: &[String]) -> Vec<ProcessStat> {
+    let mut stats = Vec::new();
+
     #[cfg(target_os = "windows")]
     {
-        let output = Command::new("tasklist")
-            .args(["/FI", &format!("IMAGENAME eq {}", name), "/FO", "CSV", "/NH"])
-            .creation_flags(CREATE_NO_WINDOW)
-            .output();
+        let wanted: HashSet<String> = names
+            .iter()
+            .map(|n| n.trim().to_lowercase())
+            .filter(|n| !n.is_empty())
+            .map(|n| if n.ends_with(".exe") { n } else { n + ".exe" })
+            .collect();
+        let instances: Vec<(u32, String)> = win32::process_names()
+            .into_iter()
+            .filter(|(_, name)| wanted.contains(name))
+            .collect();
+        if instances.is_empty() {
+            return stats;
+        }
 
-        if let Ok(output) = output {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            // If the process is found, tasklist returns its info line
-            // If not found, it returns "INFO: No tasks are running..."
-            return stdout.to_lowercase().contains(&name.to_lowercase())
-                && !stdout.contains("No tasks are running");
+        let before: std::collections::HashMap<u32, u64> = instances
+            .iter()
+            .filter_map(|(pid, _)| win32::process_usage(*pid).map(|(cpu, _)| (*pid, cpu)))
+            .collect();
+        let started = std::time::Instant::now();
+        std::thread::sleep(CPU_SAMPLE);
+        // CPU times are in 100ns units
+        let elapsed = started.elapsed().as_nanos() as f64 / 100.0;
+        let cores = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1) as f64;
+
+        for (pid, name) in instances {
+            let (Some(cpu_before), Some((cpu_after, memory_bytes))) =
+                (before.get(&pid), win32::process_usage(pid))
+            else {
+                continue;
+            };
+            let busy = cpu_after.saturating_sub(*cpu_before) as f64;
+            stats.push(ProcessStat {
+                name,
+                pid,
+                cpu_percent: (busy / elapsed / cores * 100.0) as f32,
+                memory_bytes,
+            });
         }
     }
-    false
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = names;
+
+    stats
+}
+
+pub fn is_running(name: &str) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        win32::process_names()
+            .values()
+            .any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = name;
+        false
+    }
 }
 
 pub fn kill_process(name: &str) -> Result<(), String> {
@@ -71,3 +180,195 @@ pub fn kill_process(name: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Kills several processes, one taskkill per name so each gets its own
+/// result. Names are trimmed and deduped, empty ones skipped; a name ending
+/// in `*` goes through `kill_process_pattern`.
+pub fn kill_processes(names: &[String]) -> Vec<(String, Result<(), String>)> {
+    let mut unique: Vec<String> = Vec::new();
+    for name in names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        if !unique.iter().any(|u| u.eq_ignore_ascii_case(name)) {
+            unique.push(name.to_string());
+        }
+    }
+
+    let running = get_running_processes();
+    unique
+        .into_iter()
+        .map(|name| {
+            let result = if name.ends_with('*') {
+                kill_process_pattern(&name).map(|_| ())
+            } else if !running.contains(&name.to_lowercase()) {
+                Err("Not running".to_string())
+            } else {
+                kill_process(&name)
+            };
+            (name, result)
+        })
+        .collect()
+}
+
+/// System processes a wildcard kill must never touch.
+const CRITICAL_PROCESSES: &[&str] = &[
+    "system",
+    "registry",
+    "smss.exe",
+    "csrss.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "services.exe",
+    "lsass.exe",
+    "svchost.exe",
+    "dwm.exe",
+    "explorer.exe",
+    "sihost.exe",
+    "fontdrvhost.exe",
+    "ctfmon.exe",
+    "conhost.exe",
+];
+
+pub fn is_critical(name: &str) -> bool {
+    CRITICAL_PROCESSES
+        .iter()
+        .any(|c| c.eq_ignore_ascii_case(name))
+}
+
+/// Kills every running process matching `pattern`, where a trailing `*`
+/// matches any suffix (`chrome*` also gets `chrome_crashpad.exe`). Critical
+/// system processes are never matched. Returns the names that were killed.
+pub fn kill_process_pattern(pattern: &str) -> Result<Vec<String>, String> {
+    let pattern = pattern.trim().to_lowercase();
+    let Some(prefix) = pattern.strip_suffix('*') else {
+        kill_process(&pattern)?;
+        return Ok(vec![pattern]);
+    };
+    if prefix.len() < 3 {
+        return Err(format!("Pattern '{}' is too broad", pattern));
+    }
+
+    let mut killed = Vec::new();
+    for name in get_running_processes() {
+        if !name.starts_with(prefix) || is_critical(&name) {
+            continue;
+        }
+        match kill_process(&name) {
+            Ok(()) => killed.push(name),
+            Err(e) => eprintln!("Failed to kill '{}': {}", name, e),
+        }
+    }
+    Ok(killed)
+}
+
+/// Asks the process to close (WM_CLOSE via taskkill without /F), then
+/// force-kills it if it's still running after a short grace period.
+pub fn close_process(name: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/IM", name])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output();
+
+        for _ in 0..30 {
+            if !is_running(name) {
+                return Ok(());
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+    }
+
+    kill_process(name)
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use std::collections::HashMap;
+    use windows::core::BOOL;
+    use windows::Win32::Foundation::{CloseHandle, FILETIME, HWND, LPARAM};
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+    use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+        IsWindowVisible,
+    };
+
+    /// Lowercased exe names of running processes by pid.
+    pub fn process_names() -> HashMap<u32, String> {
+        let mut names = HashMap::new();
+        unsafe {
+            let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+                return names;
+            };
+            let mut entry = PROCESSENTRY32W {
+                dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+                ..Default::default()
+            };
+            let mut next = Process32FirstW(snapshot, &mut entry);
+            while next.is_ok() {
+                let len = entry
+                    .szExeFile
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(entry.szExeFile.len());
+                let name = String::from_utf16_lossy(&entry.szExeFile[..len]);
+                names.insert(entry.th32ProcessID, name.to_lowercase());
+                next = Process32NextW(snapshot, &mut entry);
+            }
+            let _ = CloseHandle(snapshot);
+        }
+        names
+    }
+
+    /// Total CPU time (in 100ns units) and working set bytes of a process, or
+    /// `None` if it's gone or can't be opened.
+    pub fn process_usage(pid: u32) -> Option<(u64, u64)> {
+        let ticks = |t: FILETIME| (u64::from(t.dwHighDateTime) << 32) | u64::from(t.dwLowDateTime);
+        unsafe {
+            let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let (mut created, mut exited, mut kernel, mut user) = Default::default();
+            let cpu = GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user)
+                .ok()
+                .map(|_| ticks(kernel) + ticks(user));
+            let mut counters = PROCESS_MEMORY_COUNTERS {
+                cb: std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+                ..Default::default()
+            };
+            let memory = GetProcessMemoryInfo(handle, &mut counters, counters.cb)
+                .map(|_| counters.WorkingSetSize as u64)
+                .unwrap_or(0);
+            let _ = CloseHandle(handle);
+            Some((cpu?, memory))
+        }
+    }
+
+    /// Pid and title of every visible top-level window that has a title.
+    pub fn top_level_windows() -> Vec<(u32, String)> {
+        unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+            let windows = &mut *(lparam.0 as *mut Vec<(u32, String)>);
+            if IsWindowVisible(hwnd).as_bool() {
+                let len = GetWindowTextLengthW(hwnd);
+                if len > 0 {
+                    let mut buf = vec![0u16; len as usize + 1];
+                    let copied = GetWindowTextW(hwnd, &mut buf).max(0) as usize;
+                    let mut pid = 0;
+                    GetWindowThreadProcessId(hwnd, Some(&mut pid));
+                    windows.push((pid, String::from_utf16_lossy(&buf[..copied])));
+                }
+            }
+            // Keep enumerating
+            BOOL(1)
+        }
+
+        let mut windows: Vec<(u32, String)> = Vec::new();
+        unsafe {
+            let _ = EnumWindows(Some(collect), LPARAM(&mut windows as *mut _ as isize));
+        }
+        windows
+    }
+}
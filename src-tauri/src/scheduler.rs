@@ -1,61 +1,200 @@
-use crate::config;
-use crate::launcher;
-use chrono::Datelike;
+use crate::commands::{self, LaunchState, SchedulerState};
+use crate::conditions;
+use crate::config::{self, Profile, ScheduleTrigger};
+use crate::error::WorkSwitchError;
+use crate::tray;
+use chrono::{Datelike, NaiveDateTime, TimeDelta, Timelike};
 use std::collections::HashSet;
-use tauri::Emitter;
+use std::sync::atomic::Ordering;
+use tauri::{Emitter, Manager};
+
+/// Longest gap (e.g. after sleep) that missed minutes are replayed for
+const MAX_CATCH_UP_MINUTES: i64 = 10;
 
 pub fn run_scheduler(app: tauri::AppHandle) {
-    let mut last_triggered: HashSet<String> = HashSet::new();
-    let mut last_minute: String = String::new();
+    let mut last_triggered: HashSet<(String, NaiveDateTime)> = HashSet::new();
+    let mut last_minute: Option<NaiveDateTime> = None;
 
     loop {
         std::thread::sleep(std::time::Duration::from_secs(30));
 
-        let now = chrono::Local::now();
-        let current_time = now.format("%H:%M").to_string();
-        let current_day = now.weekday().num_days_from_sunday() as u8;
+        let now = chrono::Local::now().naive_local();
+        let current_minute = now
+            .with_second(0)
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(now);
+
+        // Check every minute since the last tick so jitter can't skip one
+        let first_minute = match last_minute {
+            Some(last) if last < current_minute => (last + TimeDelta::minutes(1))
+                .max(current_minute - TimeDelta::minutes(MAX_CATCH_UP_MINUTES - 1)),
+            _ => current_minute,
+        };
+        last_minute = Some(current_minute);
 
-        // Reset triggers when the minute changes
-        if current_time != last_minute {
-            last_triggered.clear();
-            last_minute = current_time.clone();
+        // Paused minutes still advance last_minute so resuming doesn't replay them
+        if is_paused(&app) {
+            continue;
         }
+        last_triggered.retain(|(_, minute)| *minute >= first_minute);
 
         let cfg = config::load_config();
 
-        for profile in &cfg.profiles {
-            if let Some(schedule) = &profile.schedule {
-                if !schedule.enabled {
-                    continue;
-                }
-                if schedule.time != current_time {
-                    continue;
-                }
-                if !schedule.days.is_empty() && !schedule.days.contains(&current_day) {
-                    continue;
-                }
-                if last_triggered.contains(&profile.id) {
-                    continue;
+        let mut minute = first_minute;
+        while minute <= current_minute {
+            let time = minute.format("%H:%M").to_string();
+            let day = minute.weekday().num_days_from_sunday() as u8;
+
+            for profile in &cfg.profiles {
+                if let Some(schedule) = &profile.schedule {
+                    if !schedule.enabled || schedule.trigger != ScheduleTrigger::Time {
+                        continue;
+                    }
+                    if schedule.time != time {
+                        continue;
+                    }
+                    if !schedule.days.is_empty() && !schedule.days.contains(&day) {
+                        continue;
+                    }
+                    if !last_triggered.insert((profile.id.clone(), minute)) {
+                        continue;
+                    }
+
+                    launch_scheduled(&app, profile, cfg.settings.launch_delay_ms);
                 }
+            }
+            minute += TimeDelta::minutes(1);
+        }
+    }
+}
+
+/// Fires every enabled profile whose schedule triggers on session unlock.
+pub fn run_unlock_triggers(app: &tauri::AppHandle) {
+    if is_paused(app) {
+        return;
+    }
+    let current_day = chrono::Local::now().weekday().num_days_from_sunday() as u8;
+    let cfg = config::load_config();
+
+    for profile in &cfg.profiles {
+        if let Some(schedule) = &profile.schedule {
+            if !schedule.enabled || schedule.trigger != ScheduleTrigger::OnUnlock {
+                continue;
+            }
+            if !schedule.days.is_empty() && !schedule.days.contains(&current_day) {
+                continue;
+            }
+            launch_scheduled(app, profile, cfg.settings.launch_delay_ms);
+        }
+    }
+}
+
+fn is_paused(app: &tauri::AppHandle) -> bool {
+    app.state::<SchedulerState>().paused.load(Ordering::SeqCst)
+}
+
+/// Pauses or resumes every schedule, keeping the tray checkbox and the
+/// window in sync.
+pub fn set_paused(app: &tauri::AppHandle, paused: bool) {
+    app.state::<SchedulerState>()
+        .paused
+        .store(paused, Ordering::SeqCst);
+    let _ = tray::rebuild_tray_menu(app, &config::load_config());
+    let _ = app.emit("scheduler-paused", paused);
+}
+
+/// Turns one profile's schedule on or off, keeping the tray checkbox and the
+/// window in sync. The scheduler picks it up on its next tick.
+pub fn set_schedule_enabled(
+    app: &tauri::AppHandle,
+    profile_id: &str,
+    enabled: bool,
+) -> Result<Profile, WorkSwitchError> {
+    let mut cfg = config::load_config();
+    let profile = cfg
+        .profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| WorkSwitchError::NotFound("Profile not found".to_string()))?;
+    let Some(schedule) = profile.schedule.as_mut() else {
+        return Err(WorkSwitchError::Validation(format!(
+            "'{}' has no schedule",
+            profile.name
+        )));
+    };
+    schedule.enabled = enabled;
+    let updated = profile.clone();
+
+    config::save_config(&cfg).map_err(WorkSwitchError::Io)?;
+    let _ = tray::rebuild_tray_menu(app, &cfg);
+    let _ = app.emit("schedule-changed", &updated);
+    Ok(updated)
+}
 
-                last_triggered.insert(profile.id.clone());
+fn launch_scheduled(app: &tauri::AppHandle, profile: &Profile, default_delay: u64) {
+    if let Some(condition) = profile.schedule.as_ref().and_then(|s| s.condition.as_ref()) {
+        if let Err(reason) = conditions::check(condition) {
+            eprintln!("Skipping scheduled launch '{}': {}", profile.name, reason);
+            return;
+        }
+    }
 
-                // Launch profile steps
-                let steps: Vec<_> = profile.steps.iter().filter(|s| s.enabled).cloned().collect();
-                let profile_name = profile.name.clone();
+    // Share the manual launch guard so the two never interleave apps
+    if app.state::<LaunchState>().is_running.load(Ordering::SeqCst) {
+        skip_scheduled(app, profile);
+        return;
+    }
 
+    let steps: Vec<_> = profile.steps.iter().filter(|s| s.enabled).cloned().collect();
+    let _ = app.emit(
+        "scheduled-launch",
+        serde_json::json!({
+            "profile_id": profile.id,
+            "profile_name": profile.name,
+            "total": steps.len()
+        }),
+    );
+
+    // Same path as a manual launch, so cancel_launch stops it too. Spawned
+    // so a launch that waits doesn't hold up the other schedules.
+    let app = app.clone();
+    let profile = profile.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = commands::run_launch(
+            app.clone(),
+            steps,
+            default_delay,
+            Some(profile.id.clone()),
+            false,
+        )
+        .await;
+        match result {
+            Ok(()) => {}
+            Err(WorkSwitchError::AlreadyRunning(_)) => skip_scheduled(&app, &profile),
+            Err(e) => {
+                eprintln!("Scheduled launch '{}' failed: {}", profile.name, e);
                 let _ = app.emit(
-                    "scheduled-launch",
-                    serde_json::json!({ "profile_name": profile_name }),
+                    "scheduled-launch-failed",
+                    serde_json::json!({
+                        "profile_name": profile.name,
+                        "error": e
+                    }),
                 );
-
-                for step in &steps {
-                    if let Err(e) = launcher::launch_step(step) {
-                        eprintln!("Scheduled launch '{}' step '{}' failed: {}", profile_name, step.name, e);
-                    }
-                    std::thread::sleep(std::time::Duration::from_millis(step.delay_after.max(500)));
-                }
             }
         }
-    }
+    });
+}
+
+fn skip_scheduled(app: &tauri::AppHandle, profile: &Profile) {
+    eprintln!(
+        "Skipping scheduled launch '{}': a launch is already in progress",
+        profile.name
+    );
+    let _ = app.emit(
+        "scheduled-launch-skipped",
+        serde_json::json!({
+            "profile_name": profile.name,
+            "reason": "A launch is already in progress"
+        }),
+    );
 }
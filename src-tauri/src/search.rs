@@ -0,0 +1,278 @@
+use crate::config::{AppConfig, Step};
+use crate::launcher;
+use serde::Serialize;
+
+const MAX_RESULTS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickResultKind {
+    /// Launches the whole profile
+    Profile,
+    /// Launches just this step via `launch_profile` with a single step
+    Step,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickResult {
+    pub kind: QuickResultKind,
+    pub profile_id: String,
+    pub profile_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step_id: Option<String>,
+    pub label: String,
+    pub score: u32,
+}
+
+/// Fuzzy-matches `query` against profile and enabled step names, best first.
+/// Profiles rank above steps on equal scores.
+pub fn quick_search(cfg: &AppConfig, query: &str) -> Vec<QuickResult> {
+    let query = query.trim().to_lowercase();
+    let mut results = Vec::new();
+
+    for profile in &cfg.profiles {
+        if let Some(score) = fuzzy_score(&query, &profile.name) {
+            results.push(QuickResult {
+                kind: QuickResultKind::Profile,
+                profile_id: profile.id.clone(),
+                profile_name: profile.name.clone(),
+                step_id: None,
+                label: profile.name.clone(),
+                score,
+            });
+        }
+
+        for step in profile.steps.iter().filter(|s| s.enabled) {
+            if let Some(score) = fuzzy_score(&query, &step.name) {
+                results.push(QuickResult {
+                    kind: QuickResultKind::Step,
+                    profile_id: profile.id.clone(),
+                    profile_name: profile.name.clone(),
+                    step_id: Some(step.id.clone()),
+                    label: step.name.clone(),
+                    score,
+                });
+            }
+        }
+    }
+
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.kind.cmp(&b.kind))
+            .then_with(|| a.label.to_lowercase().cmp(&b.label.to_lowercase()))
+    });
+    results.truncate(MAX_RESULTS);
+    results
+}
+
+/// A profile with steps that use a particular app.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileRef {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub step_ids: Vec<String>,
+}
+
+/// Profiles whose steps (including pre- and post-steps) launch `needle`: the
+/// target, before or after env expansion, equals it, or the process name
+/// contains it. Case-insensitive.
+pub fn find_profiles_using(cfg: &AppConfig, needle: &str) -> Vec<ProfileRef> {
+    let needle = needle.trim().to_lowercase();
+    if needle.is_empty() {
+        return vec![];
+    }
+
+    let uses = |step: &Step| {
+        if !step.process_name.is_empty() && step.process_name.to_lowercase().contains(&needle) {
+            return true;
+        }
+        let target = step.target.as_deref().unwrap_or("");
+        if target.is_empty() {
+            return false;
+        }
+        let resolved = launcher::preview_step(&Step {
+            check_running: Some(false),
+            ..step.clone()
+        })
+        .resolved;
+        target.to_lowercase() == needle || resolved.to_lowercase() == needle
+    };
+
+    cfg.profiles
+        .iter()
+        .filter_map(|profile| {
+            let step_ids: Vec<String> = profile
+                .pre_steps
+                .iter()
+                .chain(&profile.steps)
+                .chain(&profile.post_steps)
+                .filter(|s| uses(s))
+                .map(|s| s.id.clone())
+                .collect();
+            (!step_ids.is_empty()).then(|| ProfileRef {
+                profile_id: profile.id.clone(),
+                profile_name: profile.name.clone(),
+                step_ids,
+            })
+        })
+        .collect()
+}
+
+/// Scores `text` if every character of the (lowercase) query appears in it in
+/// order. Consecutive runs, word starts and a leading match score higher.
+fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut prev_match: Option<usize> = None;
+
+    for qc in query.chars() {
+        let idx = pos + text[pos..].iter().position(|&c| c == qc)?;
+        score += 1;
+        if prev_match.is_some_and(|p| p + 1 == idx) {
+            score += 5;
+        }
+        if idx == 0 {
+            score += 10;
+        } else if !text[idx - 1].is_alphanumeric() {
+            score += 8;
+        }
+        prev_match = Some(idx);
+        pos = idx + 1;
+    }
+
+    // Prefer tighter names for the same match
+    Some(score * 100 / (text.len() as u32 + 10))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Profile;
+
+    fn step(id: &str, name: &str) -> Step {
+        Step {
+            id: id.to_string(),
+            name: name.to_string(),
+            step_type: "app".to_string(),
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn profile(id: &str, name: &str, steps: Vec<Step>) -> Profile {
+        Profile {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            steps,
+            tags: Vec::new(),
+            hotkey: String::new(),
+            schedule: None,
+            kill_on_switch: Vec::new(),
+            pre_steps: Vec::new(),
+            post_steps: Vec::new(),
+        }
+    }
+
+    fn config(profiles: Vec<Profile>) -> AppConfig {
+        AppConfig {
+            profiles,
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn profile_ranks_above_step_with_the_same_score() {
+        let cfg = config(vec![profile("p", "Code", vec![step("s", "Code")])]);
+        let results = quick_search(&cfg, "code");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].score, results[1].score);
+        assert_eq!(results[0].kind, QuickResultKind::Profile);
+        assert_eq!(results[1].kind, QuickResultKind::Step);
+    }
+
+    #[test]
+    fn query_characters_must_match_in_order() {
+        assert!(fuzzy_score("vsc", "VS Code").is_some());
+        assert!(fuzzy_score("csv", "VS Code").is_none());
+    }
+
+    #[test]
+    fn empty_query_returns_profiles_and_enabled_steps() {
+        let mut disabled = step("off", "Disabled");
+        disabled.enabled = false;
+        let cfg = config(vec![profile(
+            "p",
+            "Work",
+            vec![step("a", "Slack"), step("b", "Mail"), disabled],
+        )]);
+        let results = quick_search(&cfg, "  ");
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| r.step_id.as_deref() != Some("off")));
+    }
+
+    #[test]
+    fn results_are_capped() {
+        let profiles = (0..MAX_RESULTS + 5)
+            .map(|i| profile(&i.to_string(), &format!("Profile {}", i), Vec::new()))
+            .collect();
+        assert_eq!(quick_search(&config(profiles), "").len(), MAX_RESULTS);
+    }
+
+    fn app_step(id: &str, target: &str, process_name: &str) -> Step {
+        Step {
+            target: Some(target.to_string()),
+            process_name: process_name.to_string(),
+            ..step(id, id)
+        }
+    }
+
+    #[test]
+    fn process_name_matches_by_substring_but_target_must_be_exact() {
+        let cfg = config(vec![profile(
+            "p",
+            "Browsing",
+            vec![
+                app_step("chrome", r"C:\Apps\chrome.exe", "chrome.exe"),
+                app_step("code", r"C:\Apps\Code.exe", ""),
+            ],
+        )]);
+
+        let by_process = find_profiles_using(&cfg, "Chrome");
+        assert_eq!(by_process.len(), 1);
+        assert_eq!(by_process[0].step_ids, ["chrome"]);
+
+        assert!(find_profiles_using(&cfg, "code.exe").is_empty());
+        let by_target = find_profiles_using(&cfg, r"c:\apps\code.exe");
+        assert_eq!(by_target[0].step_ids, ["code"]);
+    }
+
+    #[test]
+    fn target_matches_after_env_expansion() {
+        std::env::set_var("WORKSWITCH_TEST_APPS", r"C:\Tools");
+        let cfg = config(vec![profile(
+            "p",
+            "Tools",
+            vec![app_step("tool", r"%WORKSWITCH_TEST_APPS%\tool.exe", "")],
+        )]);
+        let found = find_profiles_using(&cfg, r"C:\Tools\tool.exe");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].step_ids, ["tool"]);
+    }
+
+    #[test]
+    fn hook_steps_are_included() {
+        let mut p = profile("p", "Remote", vec![app_step("main", "ssh.exe", "ssh.exe")]);
+        p.pre_steps = vec![app_step("vpn", "vpn.exe", "vpnclient.exe")];
+        p.post_steps = vec![app_step("disconnect", "vpn.exe", "vpnclient.exe")];
+        let found = find_profiles_using(&config(vec![p]), "vpnclient");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].step_ids, ["vpn", "disconnect"]);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::config;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Runtime state that should survive a restart but doesn't belong in
+/// config.json (the frontend rewrites that file wholesale on every save).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(default)]
+    pub active_profile_id: Option<String>,
+    /// Keyed by profile id
+    #[serde(default)]
+    pub profile_stats: HashMap<String, ProfileStats>,
+    /// Process names WorkSwitch launched and hasn't closed yet
+    #[serde(default)]
+    pub launched_processes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileStats {
+    /// Unix seconds of the last completed launch
+    #[serde(default)]
+    pub last_launched: Option<i64>,
+    #[serde(default)]
+    pub launch_count: u32,
+}
+
+pub fn session_path() -> PathBuf {
+    config::config_path().with_file_name("session.json")
+}
+
+pub fn load_session() -> Session {
+    match fs::read_to_string(session_path()) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+        Err(_) => Session::default(),
+    }
+}
+
+pub fn save_session(session: &Session) -> Result<(), String> {
+    let path = session_path();
+    let json = serde_json::to_string_pretty(session).map_err(|e| e.to_string())?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    config::retry_io(|| fs::write(&tmp_path, &json))
+        .map_err(|e| format!("Failed to write session: {}", e))?;
+    config::retry_io(|| fs::rename(&tmp_path, &path))
+        .map_err(|e| format!("Failed to rename session: {}", e))?;
+
+    Ok(())
+}
+
+/// Bumps the launch count and timestamp for a profile.
+pub fn record_launch(profile_id: &str) -> Result<(), String> {
+    let mut session = load_session();
+    let stats = session
+        .profile_stats
+        .entry(profile_id.to_string())
+        .or_default();
+    stats.last_launched = Some(chrono::Utc::now().timestamp());
+    stats.launch_count += 1;
+    save_session(&session)
+}
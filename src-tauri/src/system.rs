@@ -0,0 +1,114 @@
+use crate::config;
+use serde::Serialize;
+
+/// What a bug report needs to know about this install.
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub app_version: String,
+    /// `windows`, `macos`, `linux`, ...
+    pub os: String,
+    /// e.g. "Windows 11 Pro 23H2 (build 22631)"; empty if unknown
+    pub os_version: String,
+    pub arch: String,
+    pub config_path: String,
+}
+
+pub fn system_info(app_version: String) -> SystemInfo {
+    SystemInfo {
+        app_version,
+        os: std::env::consts::OS.to_string(),
+        os_version: os_version(),
+        arch: std::env::consts::ARCH.to_string(),
+        config_path: config::config_path().to_string_lossy().to_string(),
+    }
+}
+
+fn os_version() -> String {
+    #[cfg(target_os = "windows")]
+    {
+        use winreg::enums::*;
+        use winreg::RegKey;
+
+        let Ok(key) = RegKey::predef(HKEY_LOCAL_MACHINE)
+            .open_subkey(r"SOFTWARE\Microsoft\Windows NT\CurrentVersion")
+        else {
+            return String::new();
+        };
+        let read = |name: &str| key.get_value::<String, _>(name).unwrap_or_default();
+        let build = read("CurrentBuild");
+
+        // ProductName still says "Windows 10" on Windows 11
+        let mut product = read("ProductName");
+        if build.parse::<u32>().is_ok_and(|b| b >= 22000) {
+            product = product.replace("Windows 10", "Windows 11");
+        }
+        let release = read("DisplayVersion");
+        let mut version = product;
+        if !release.is_empty() {
+            version = format!("{} {}", version, release);
+        }
+        if !build.is_empty() {
+            version = format!("{} (build {})", version, build);
+        }
+        version.trim().to_string()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        String::new()
+    }
+}
+
+/// Bytes free to the current user on the volume holding `path`.
+pub fn free_space(path: &str) -> Result<u64, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::core::HSTRING;
+        use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+        let mut free = 0u64;
+        unsafe { GetDiskFreeSpaceExW(&HSTRING::from(path), Some(&mut free), None, None) }
+            .map_err(|e| format!("Failed to read free space for {}: {}", path, e))?;
+        Ok(free)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        // POSIX output keeps each volume on one line, sizes in 1024-byte blocks
+        let output = std::process::Command::new("df")
+            .args(["-Pk", path])
+            .output()
+            .map_err(|e| format!("Failed to run df: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to read free space for {}: {}",
+                path,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        df_available_bytes(&String::from_utf8_lossy(&output.stdout))
+            .ok_or_else(|| format!("Unexpected df output for {}", path))
+    }
+}
+
+/// The available space from `df -Pk` output, in bytes.
+#[cfg(any(not(target_os = "windows"), test))]
+fn df_available_bytes(output: &str) -> Option<u64> {
+    // Filesystem 1024-blocks Used Available Capacity Mounted on
+    let line = output.lines().nth(1)?;
+    let available: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(available * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn df_available_is_read_from_the_fourth_column() {
+        let output = "Filesystem     1024-blocks     Used Available Capacity Mounted on\n\
+                      /dev/sda1        102400000 51200000  48000000      52% /\n";
+        assert_eq!(df_available_bytes(output), Some(48_000_000 * 1024));
+        assert_eq!(df_available_bytes("Filesystem\n"), None);
+    }
+}
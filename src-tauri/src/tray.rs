@@ -1,17 +1,34 @@
-use crate::config::AppConfig;
+use crate::commands::{ActiveProfile, SchedulerState};
+use crate::config::{AppConfig, Profile, ScheduleTrigger, TrayLayout};
+use crate::session;
+use std::sync::atomic::Ordering;
 use tauri::{
     image::Image,
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder},
     tray::TrayIconBuilder,
     Emitter, Manager,
 };
 
-fn create_default_icon() -> Image<'static> {
-    // Create a simple 32x32 RGBA icon (blue square)
+/// Recently launched profiles shown above the full list
+const MAX_RECENT: usize = 3;
+/// Longest profile description shown after its name in the menu
+const MAX_DESCRIPTION_CHARS: usize = 40;
+
+fn create_icon(theme: &str) -> Image<'static> {
+    // A 32x32 RGBA blue square with a border that contrasts with the taskbar
+    let (fill, border) = if theme == "light" {
+        ([29, 78, 216, 255], [17, 24, 39, 255]) // #1d4ed8 on #111827
+    } else {
+        ([59, 130, 246, 255], [243, 244, 246, 255]) // #3b82f6 on #f3f4f6
+    };
+
     let size = 32u32;
     let mut rgba = Vec::with_capacity((size * size * 4) as usize);
-    for _ in 0..size * size {
-        rgba.extend_from_slice(&[37, 99, 235, 255]); // #2563eb blue
+    for y in 0..size {
+        for x in 0..size {
+            let edge = x < 2 || y < 2 || x >= size - 2 || y >= size - 2;
+            rgba.extend_from_slice(if edge { &border } else { &fill });
+        }
     }
     Image::new_owned(rgba, size, size)
 }
@@ -21,7 +38,7 @@ pub fn create_tray(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Err
     let menu = build_tray_menu(app, &config)?;
 
     let _tray = TrayIconBuilder::with_id("main")
-        .icon(create_default_icon())
+        .icon(create_icon(&config.settings.theme))
         .menu(&menu)
         .tooltip("WorkSwitch")
         .on_menu_event(move |app, event| {
@@ -32,9 +49,27 @@ pub fn create_tray(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Err
                     let _ = window.unminimize();
                     let _ = window.set_focus();
                 }
+            } else if id == "pause-scheduler" {
+                let paused = app.state::<SchedulerState>().paused.load(Ordering::SeqCst);
+                crate::scheduler::set_paused(app, !paused);
+            } else if let Some(profile_id) = id.strip_prefix("schedule-") {
+                let enabled = crate::config::load_config()
+                    .profiles
+                    .iter()
+                    .find(|p| p.id == profile_id)
+                    .and_then(|p| p.schedule.as_ref())
+                    .is_some_and(|s| s.enabled);
+                if let Err(e) = crate::scheduler::set_schedule_enabled(app, profile_id, !enabled) {
+                    eprintln!("Failed to toggle schedule: {}", e);
+                }
             } else if id == "quit" {
-                app.exit(0);
-            } else if let Some(profile_id) = id.strip_prefix("profile-") {
+                if !crate::lifecycle::close_apps_on_exit(app) {
+                    app.exit(0);
+                }
+            } else if let Some(profile_id) = id
+                .strip_prefix("profile-")
+                .or_else(|| id.strip_prefix("recent-"))
+            {
                 let _ = app.emit("tray-launch-profile", profile_id.to_string());
             }
         })
@@ -57,25 +92,108 @@ pub fn create_tray(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Err
     Ok(())
 }
 
+/// "Launch: Name - description", with the description cut short. Menu items
+/// have no tooltips, so this is the only place the tray can show it.
+fn launch_label(profile: &Profile) -> String {
+    let description = profile
+        .description
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if description.is_empty() {
+        return format!("Launch: {}", profile.name);
+    }
+    let short: String = if description.chars().count() > MAX_DESCRIPTION_CHARS {
+        let cut: String = description
+            .chars()
+            .take(MAX_DESCRIPTION_CHARS - 1)
+            .collect();
+        format!("{}\u{2026}", cut.trim_end())
+    } else {
+        description
+    };
+    format!("Launch: {} - {}", profile.name, short)
+}
+
 fn build_tray_menu(
     app: &tauri::AppHandle,
     config: &AppConfig,
 ) -> Result<tauri::menu::Menu<tauri::Wry>, Box<dyn std::error::Error>> {
     let mut builder = MenuBuilder::new(app);
 
-    // Show WorkSwitch
+    // Show WorkSwitch, above the profiles unless they come first
     let show_item = MenuItemBuilder::with_id("show", "Show WorkSwitch").build(app)?;
-    builder = builder.item(&show_item).separator();
+    let profiles_first = config.settings.tray_layout == TrayLayout::ProfilesFirst;
+    if !profiles_first {
+        builder = builder.item(&show_item).separator();
+    }
+
+    // Recently launched profiles, when the full list is longer than this
+    let stats = session::load_session().profile_stats;
+    let mut recent: Vec<_> = config
+        .profiles
+        .iter()
+        .filter_map(|p| Some((stats.get(&p.id)?.last_launched?, p)))
+        .collect();
+    if config.profiles.len() > MAX_RECENT && !recent.is_empty() {
+        recent.sort_by_key(|(launched, _)| std::cmp::Reverse(*launched));
+        let header = MenuItemBuilder::with_id("recent", "Recent")
+            .enabled(false)
+            .build(app)?;
+        builder = builder.item(&header);
+        for (_, profile) in recent.iter().take(MAX_RECENT) {
+            let item =
+                MenuItemBuilder::with_id(format!("recent-{}", profile.id), launch_label(profile))
+                    .build(app)?;
+            builder = builder.item(&item);
+        }
+        builder = builder.separator();
+    }
 
-    // Profile items
+    // Profile items (the active profile gets a checkmark)
+    let active_id = app.try_state::<ActiveProfile>().and_then(|a| a.get());
     for profile in &config.profiles {
-        let item = MenuItemBuilder::with_id(
-            format!("profile-{}", profile.id),
-            format!("Launch: {}", profile.name),
-        )
-        .build(app)?;
+        let item =
+            CheckMenuItemBuilder::with_id(format!("profile-{}", profile.id), launch_label(profile))
+                .checked(active_id.as_deref() == Some(profile.id.as_str()))
+                .build(app)?;
         builder = builder.item(&item);
     }
+    if profiles_first {
+        builder = builder.separator().item(&show_item);
+    }
+
+    // One checkbox per scheduled profile
+    let scheduled: Vec<_> = config
+        .profiles
+        .iter()
+        .filter_map(|p| Some((p, p.schedule.as_ref()?)))
+        .collect();
+    if !scheduled.is_empty() {
+        builder = builder.separator();
+        for (profile, schedule) in scheduled {
+            let when = match schedule.trigger {
+                ScheduleTrigger::Time => schedule.time.clone(),
+                ScheduleTrigger::OnUnlock => "on unlock".to_string(),
+            };
+            let item = CheckMenuItemBuilder::with_id(
+                format!("schedule-{}", profile.id),
+                format!("Schedule: {} ({})", profile.name, when),
+            )
+            .checked(schedule.enabled)
+            .build(app)?;
+            builder = builder.item(&item);
+        }
+    }
+
+    // Pause schedules
+    let paused = app
+        .try_state::<SchedulerState>()
+        .is_some_and(|s| s.paused.load(Ordering::SeqCst));
+    let pause_item = CheckMenuItemBuilder::with_id("pause-scheduler", "Pause Schedules")
+        .checked(paused)
+        .build(app)?;
+    builder = builder.separator().item(&pause_item);
 
     // Quit
     let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
@@ -95,3 +213,20 @@ pub fn rebuild_tray_menu(
     }
     Ok(())
 }
+
+/// Whether the tray icon exists. Without it a hidden window can't be brought
+/// back, so callers minimize instead of hiding.
+pub fn tray_available(app: &tauri::AppHandle) -> bool {
+    app.tray_by_id("main").is_some()
+}
+
+/// Regenerates the tray icon for the current theme.
+pub fn update_tray_icon(
+    app: &tauri::AppHandle,
+    config: &AppConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(tray) = app.tray_by_id("main") {
+        tray.set_icon(Some(create_icon(&config.settings.theme)))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+/// Waits for session-unlock notifications and fires the profiles scheduled to
+/// launch on unlock. Blocks the calling thread; a no-op off Windows.
+pub fn run_unlock_listener(app: tauri::AppHandle) {
+    #[cfg(target_os = "windows")]
+    {
+        use std::time::{Duration, Instant};
+
+        // A quick lock/unlock shouldn't launch everything twice
+        const UNLOCK_DEBOUNCE: Duration = Duration::from_secs(60);
+
+        let unlocks = match wts::subscribe() {
+            Ok(rx) => rx,
+            Err(e) => {
+                eprintln!("Failed to listen for session unlock: {}", e);
+                return;
+            }
+        };
+
+        let mut last_fired: Option<Instant> = None;
+        for () in unlocks {
+            if last_fired.is_some_and(|t| t.elapsed() < UNLOCK_DEBOUNCE) {
+                continue;
+            }
+            last_fired = Some(Instant::now());
+            crate::scheduler::run_unlock_triggers(&app);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod wts {
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::sync::{Mutex, OnceLock};
+    use windows::core::w;
+    use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::System::RemoteDesktop::{
+        WTSRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW,
+        TranslateMessage, HWND_MESSAGE, MSG, WINDOW_EX_STYLE, WINDOW_STYLE, WM_WTSSESSION_CHANGE,
+        WNDCLASSW, WTS_SESSION_UNLOCK,
+    };
+
+    static UNLOCK_TX: OnceLock<Mutex<Sender<()>>> = OnceLock::new();
+
+    unsafe extern "system" fn wnd_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        if msg == WM_WTSSESSION_CHANGE && wparam.0 as u32 == WTS_SESSION_UNLOCK {
+            if let Some(Ok(tx)) = UNLOCK_TX.get().map(|tx| tx.lock()) {
+                let _ = tx.send(());
+            }
+            return LRESULT(0);
+        }
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    /// Creates a hidden message-only window on its own thread and registers it
+    /// for session notifications. The receiver yields once per unlock.
+    pub fn subscribe() -> Result<Receiver<()>, String> {
+        let (tx, rx) = mpsc::channel();
+        UNLOCK_TX
+            .set(Mutex::new(tx))
+            .map_err(|_| "Already listening for session unlock".to_string())?;
+
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
+        std::thread::spawn(move || unsafe {
+            let class_name = w!("WorkSwitchSessionListener");
+            let instance: HINSTANCE = match GetModuleHandleW(None) {
+                Ok(module) => module.into(),
+                Err(e) => {
+                    let _ = ready_tx.send(Err(format!("GetModuleHandleW failed: {}", e)));
+                    return;
+                }
+            };
+
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(wnd_proc),
+                hInstance: instance,
+                lpszClassName: class_name,
+                ..Default::default()
+            };
+            if RegisterClassW(&class) == 0 {
+                let _ = ready_tx.send(Err("RegisterClassW failed".to_string()));
+                return;
+            }
+
+            let hwnd = match CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                class_name,
+                None,
+                WINDOW_STYLE::default(),
+                0,
+                0,
+                0,
+                0,
+                Some(HWND_MESSAGE),
+                None,
+                Some(instance),
+                None,
+            ) {
+                Ok(hwnd) => hwnd,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(format!("CreateWindowExW failed: {}", e)));
+                    return;
+                }
+            };
+
+            if let Err(e) = WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) {
+                let _ = ready_tx.send(Err(format!("WTSRegisterSessionNotification failed: {}", e)));
+                return;
+            }
+            let _ = ready_tx.send(Ok(()));
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+
+        ready_rx
+            .recv()
+            .map_err(|e| format!("Session listener thread exited: {}", e))??;
+        Ok(rx)
+    }
+}